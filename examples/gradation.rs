@@ -22,7 +22,10 @@ fn main() {
         Polygon::builder()
             .sides_fn(|Index { u, .. }| *u as u8 + 3)
             .size_fn(|Index { v, .. }| *v as f64 * 35.0 + 80.0)
-            .color_fn(|Index { u, v }| Color::new((*u + 1) as u8 * 25, 0, (*v + 1) as u8 * 25, 255))
+            .color_context_fn(|context| {
+                let (x, y) = context.position();
+                Color::new((x * 255.0) as u8, 0, (y * 255.0) as u8, 255)
+            })
             .build(),
     );
 
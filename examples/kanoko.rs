@@ -1,4 +1,8 @@
-use kanoko::{Canvas, point_set::lattice::Lattice, shape::Polygon};
+use kanoko::{
+    Canvas,
+    point_set::lattice::Lattice,
+    shape::{Compound, Polygon},
+};
 
 /// An example with minimal randomization, based on the traditional Japanese tie-dye pattern
 fn main() {
@@ -13,20 +17,22 @@ fn main() {
                 .build(),
         );
 
-    canvas_builder.add_shape(
+    let mut ring = Compound::new();
+    ring.add_shape(
         Polygon::builder()
             .sides(4)
             .size(70.0)
             .color("#f5f5fa".try_into().unwrap())
             .build(),
     );
-    canvas_builder.add_shape(
+    ring.add_shape(
         Polygon::builder()
             .sides(4)
             .size(35.0)
             .color(background_color)
             .build(),
     );
+    canvas_builder.add_shape(ring);
 
     let canvas = canvas_builder.build();
     let document = canvas.render(|_| true);
@@ -0,0 +1,41 @@
+use kanoko::{
+    Canvas,
+    point_set::{PointSet, neighbors::Neighborhood, poisson_disk::PoissonDisk},
+    shape::{Connector, Polygon},
+};
+
+/// An example joining neighboring points, based on kumo (spider web) shibori
+fn main() {
+    let points = PoissonDisk::builder()
+        .size(2560.0, 1440.0)
+        .r(120.0)
+        .k(30)
+        .build();
+    let edges = points.edges(Neighborhood::Delaunay);
+
+    let mut canvas_builder = Canvas::builder()
+        .size(2560.0, 1440.0)
+        .background_color("#1d2a4d".try_into().unwrap())
+        .points(points);
+
+    canvas_builder.add_shape(
+        Connector::builder()
+            .edges(edges)
+            .width(4.0)
+            .bend(0.1)
+            .color("#e8e4da".try_into().unwrap())
+            .build(),
+    );
+    canvas_builder.add_shape(
+        Polygon::builder()
+            .sides(8)
+            .size(36.0)
+            .cv(0.1)
+            .color("#e8e4da".try_into().unwrap())
+            .build(),
+    );
+
+    let canvas = canvas_builder.build();
+    let document = canvas.render(|_| true);
+    svg::save("examples/kumo.svg", &document).unwrap();
+}
@@ -1,52 +1,69 @@
-use kanoko::{Canvas, point_set::poisson_disk::PoissonDisk, shape::Polygon};
+use kanoko::{
+    Canvas,
+    point_set::{packing::Packing, poisson_disk::PoissonDisk},
+    shape::{Polygon, ShapeStack},
+};
 use rand_distr::{Distribution, weighted::WeightedIndex};
 
-/// An example using PoissonDisk as the point set, colors based off Nazar amulets
+/// An example using PoissonDisk as the point set, sized by Packing, colors based off Nazar amulets
 fn main() {
-    let size = 144.0;
+    let points = PoissonDisk::builder()
+        .size(2560.0, 1440.0)
+        .r(144.0)
+        .k(30)
+        .build();
+
+    // Each amulet grows until it touches its neighbors, leaving room for the wobble
+    let packing = Packing::builder()
+        .points(&points)
+        .gap(16.0)
+        .max(240.0)
+        .build();
+    let size_fn = |scale: f64| {
+        let size_fn = packing.size_fn();
+        move |index: &_| size_fn(index) * scale
+    };
+
     let mut canvas_builder = Canvas::builder()
         .size(2560.0, 1440.0)
         .background_color("#fff".try_into().unwrap())
-        .points(
-            PoissonDisk::builder()
-                .size(2560.0, 1440.0)
-                .r(size)
-                .k(30)
-                .build(),
-        );
+        .points(points);
 
+    // The rings share one random sample, so they wobble together
     let polygon_builder = || Polygon::builder().sides(7).cv(0.05);
-    canvas_builder.add_shape(
+    let mut amulet = ShapeStack::new();
+    amulet.add_shape(
         polygon_builder()
-            .size(size)
+            .size_fn(size_fn(1.0))
             .color("#070d97".try_into().unwrap())
             .build(),
     );
-    canvas_builder.add_shape(
+    amulet.add_shape(
         polygon_builder()
-            .size(size * 3.0 / 4.0)
+            .size_fn(size_fn(3.0 / 4.0))
             .color("#fff".try_into().unwrap())
             .build(),
     );
-    canvas_builder.add_shape(
+    amulet.add_shape(
         polygon_builder()
-            .size(size / 2.0)
+            .size_fn(size_fn(1.0 / 2.0))
             .color_fn(|_| {
-                let colors = vec!["#73bff1", "#daa520"];
-                let weights = vec![15, 1];
+                let colors = ["#73bff1", "#daa520"];
+                let weights = [15, 1];
 
-                let dist = WeightedIndex::new(&weights).unwrap();
+                let dist = WeightedIndex::new(weights).unwrap();
 
                 colors[dist.sample(&mut rand::rng())].try_into().unwrap()
             })
             .build(),
     );
-    canvas_builder.add_shape(
+    amulet.add_shape(
         polygon_builder()
-            .size(size / 4.0)
+            .size_fn(size_fn(1.0 / 4.0))
             .color("#000".try_into().unwrap())
             .build(),
     );
+    canvas_builder.add_shape(amulet);
 
     let canvas = canvas_builder.build();
     let document = canvas.render(|_| true);
@@ -1,4 +1,4 @@
-use kanoko::{Canvas, geometry::Angle, point_set::lattice::Lattice, shape::Polygon};
+use kanoko::{Canvas, geometry::Angle, layout::Fit, point_set::lattice::Lattice, shape::Polygon};
 use rand::{RngExt, seq::IndexedRandom};
 
 /// An example with lots of randomimzation
@@ -14,7 +14,10 @@ fn main() {
     let mut canvas_builder = Canvas::builder()
         .size(2560.0, 1440.0)
         .background_color("#e0d8d1".try_into().unwrap())
-        .points(lattice);
+        .points(lattice)
+        // Whatever size the lattice is, it is scaled along with its shapes to cover the canvas
+        .fit(Fit::Cover)
+        .scale_shapes(true);
 
     let size = rng
         .random_range(100.0..lattice.len_a)
@@ -25,7 +28,7 @@ fn main() {
             .sides_fn(|_| rand::rng().random_range(3..8))
             .size(size)
             .color_fn(|_| {
-                vec![
+                [
                     "#6f6e6a", "#b3b4af", "#b09e90", "#bea24e", "#d9bdb9", "#9a9ba0",
                 ]
                 .choose(&mut rand::rng())
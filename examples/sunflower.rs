@@ -1,4 +1,10 @@
-use kanoko::{Canvas, geometry::Angle, point_set::vogel::Vogel, shape::Polygon};
+use kanoko::{
+    Canvas,
+    geometry::Angle,
+    layout::{Fit, Margin},
+    point_set::vogel::Vogel,
+    shape::Polygon,
+};
 
 /// An example using Vogel as the point set
 fn main() {
@@ -10,6 +16,8 @@ fn main() {
         .size(2560.0, 1440.0)
         .background_color("#fcba03".try_into().unwrap())
         .points(points)
+        .fit(Fit::Contain)
+        .margin(Margin::uniform(80.0))
         .build();
 
     let polygon_builder = || {
@@ -0,0 +1,263 @@
+//! What is drawn behind the shapes of the [`Canvas`](crate::Canvas)
+use std::io;
+
+use svg::node::element::{Element, Group, Image, LinearGradient, RadialGradient, Rectangle, Stop};
+
+use crate::{
+    Color, Error,
+    effect::{Effect, definition_id},
+    geometry::{Angle, BoundingBox, Coordinate},
+};
+
+/// A pattern that can be drawn as a [`Background`], such as another [`Canvas`](crate::Canvas)
+pub trait Pattern {
+    /// Render the whole pattern as a group
+    ///
+    /// Definitions such as filters can be left inside the group, as they are gathered into the
+    /// `defs` of the document.
+    fn render_pattern(&self) -> Group;
+}
+
+/// The background of the [`Canvas`](crate::Canvas)
+///
+/// A [`Color`] can be used directly as a background, or the canvas can be painted with a gradient:
+///
+/// ```rust,ignore
+/// let canvas = Canvas::builder()
+///     .background(Background::LinearGradient {
+///         angle: Angle::Degree(90.0),
+///         stops: vec![(0.0, indigo), (1.0, white)],
+///     })
+///     // ...
+/// ```
+pub enum Background {
+    /// A single color
+    Color(Color),
+
+    /// A linear gradient across the whole canvas, at `angle` clockwise from left to right
+    ///
+    /// Each stop is an offset from `0.0` to `1.0` along the gradient, and its color.
+    LinearGradient {
+        angle: Angle,
+        stops: Vec<(f64, Color)>,
+    },
+
+    /// A radial gradient out from `center`
+    ///
+    /// The `center` is normalized to the canvas, so that `(0.5, 0.5)` is its center. A `radius` of
+    /// `1.0` reaches the corner furthest from the center.
+    RadialGradient {
+        center: (f64, f64),
+        radius: f64,
+        stops: Vec<(f64, Color)>,
+    },
+
+    /// A color with a fabric or paper texture, using an [`Effect::Grain`]
+    ///
+    /// Using a different `frequency` along x and y gives the look of woven threads, while equal
+    /// frequencies give the look of paper.
+    Texture {
+        color: Color,
+        frequency: (f64, f64),
+        intensity: f64,
+        seed: u32,
+    },
+
+    /// An image covering the canvas, cropped to keep its aspect ratio
+    ///
+    /// The `href` can be a path or URL of the image, or a data URI as made by
+    /// [`Background::image`].
+    Image { href: String },
+
+    /// Another pattern drawn behind the shapes, such as a [`Canvas`](crate::Canvas) with a
+    /// different point set
+    Pattern(Box<dyn Pattern>),
+
+    /// No background, leaving the canvas transparent
+    None,
+}
+
+impl From<Color> for Background {
+    fn from(color: Color) -> Self {
+        Background::Color(color)
+    }
+}
+
+impl Background {
+    /// Embed a local image file as the background, so the SVG doesn't depend on the file
+    ///
+    /// PNG, JPEG, GIF, WebP and SVG images are supported.
+    pub fn image(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        let bytes = std::fs::read(path)?;
+        let mime = image_mime(&bytes).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "unsupported image format")
+        })?;
+
+        Ok(Background::Image {
+            href: format!("data:{mime};base64,{}", base64(&bytes)),
+        })
+    }
+
+    /// Render the background covering `bb`, or `None` if nothing is drawn
+    pub(crate) fn render(&self, bb: BoundingBox) -> Option<Element> {
+        let (left, top) = bb.min().to_cartesian();
+        let (width, height) = bb.span().to_cartesian();
+        let rectangle = Rectangle::new()
+            .set("x", round(left))
+            .set("y", round(top))
+            .set("width", round(width))
+            .set("height", round(height));
+
+        let element = match self {
+            Background::Color(color) => rectangle
+                .set("fill", color.to_svg_color())
+                .set("fill-opacity", format!("{:.3}", color.to_opacity_percent()))
+                .into(),
+            Background::LinearGradient { angle, stops } => {
+                // The gradient runs between the lines through the corners that are furthest
+                // along it, as in CSS
+                let (sin, cos) = angle.to_radian().sin_cos();
+                let length = (width * cos).abs() + (height * sin).abs();
+                let (dx, dy) = (cos * length / 2.0, sin * length / 2.0);
+                let (cx, cy) = (left + width / 2.0, top + height / 2.0);
+
+                let gradient = LinearGradient::new()
+                    .set("gradientUnits", "userSpaceOnUse")
+                    .set("x1", round(cx - dx))
+                    .set("y1", round(cy - dy))
+                    .set("x2", round(cx + dx))
+                    .set("y2", round(cy + dy));
+                gradient_fill(rectangle, add_stops(gradient, stops))
+            }
+            Background::RadialGradient {
+                center,
+                radius,
+                stops,
+            } => {
+                let center = Coordinate::Cartesian {
+                    x: left + center.0 * width,
+                    y: top + center.1 * height,
+                };
+                let furthest = [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)]
+                    .into_iter()
+                    .map(|(x, y)| {
+                        center.dist(&Coordinate::Cartesian {
+                            x: left + x,
+                            y: top + y,
+                        })
+                    })
+                    .fold(0.0, f64::max);
+                let (cx, cy) = center.to_rounded_cartesian(3);
+
+                let gradient = RadialGradient::new()
+                    .set("gradientUnits", "userSpaceOnUse")
+                    .set("cx", cx)
+                    .set("cy", cy)
+                    .set("r", round(radius * furthest));
+                gradient_fill(rectangle, add_stops(gradient, stops))
+            }
+            Background::Texture {
+                color,
+                frequency,
+                intensity,
+                seed,
+            } => {
+                let grain = Effect::Grain {
+                    frequency: *frequency,
+                    intensity: *intensity,
+                    seed: *seed,
+                };
+                grain
+                    .apply(
+                        rectangle
+                            .set("fill", color.to_svg_color())
+                            .set("fill-opacity", format!("{:.3}", color.to_opacity_percent())),
+                        bb,
+                    )
+                    .into()
+            }
+            Background::Image { href } => Image::new()
+                .set("href", href.as_str())
+                .set("x", round(left))
+                .set("y", round(top))
+                .set("width", round(width))
+                .set("height", round(height))
+                .set("preserveAspectRatio", "xMidYMid slice")
+                .into(),
+            Background::Pattern(pattern) => pattern.render_pattern().into(),
+            Background::None => return None,
+        };
+
+        Some(element)
+    }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
+
+/// Add a `stop` to `gradient` for each offset and color
+fn add_stops<T: svg::Node>(mut gradient: T, stops: &[(f64, Color)]) -> T {
+    for (offset, color) in stops {
+        gradient.append(
+            Stop::new()
+                .set("offset", offset.clamp(0.0, 1.0))
+                .set("stop-color", color.to_svg_color())
+                .set("stop-opacity", color.to_opacity_percent()),
+        );
+    }
+    gradient
+}
+
+/// Fill `rectangle` with `gradient`, keeping the definition alongside it
+///
+/// The id is derived from the gradient, so identical gradients can share a definition.
+fn gradient_fill(rectangle: Rectangle, mut gradient: impl svg::Node) -> Element {
+    let id = definition_id("gradient", &gradient.to_string());
+    gradient.assign("id", id.as_str());
+
+    Group::new()
+        .add(gradient)
+        .add(rectangle.set("fill", format!("url(#{id})")))
+        .into()
+}
+
+/// The MIME type of an image, found from the start of its file
+fn image_mime(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF8") {
+        Some("image/gif")
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        Some("image/webp")
+    } else if bytes.trim_ascii_start().starts_with(b"<") {
+        Some("image/svg+xml")
+    } else {
+        None
+    }
+}
+
+/// Encode `bytes` as standard base64, with padding
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0_u32, |n, (i, byte)| n | u32::from(*byte) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(char::from(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
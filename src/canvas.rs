@@ -1,41 +1,146 @@
+use std::{collections::BTreeMap, convert::Infallible, io, ops::Range};
+
+use itertools::Either;
 use svg::{
-    Document,
-    node::element::{Group, Rectangle},
+    Document, Node,
+    node::element::{ClipPath, Definitions, Element, Group, Rectangle, Use},
 };
 
 use crate::{
     Color,
+    background::{Background, Pattern},
+    effect::{BlendMode, Effect, take_definitions},
     geometry::{BoundingBox, Coordinate},
+    layout::{Anchor, Fit, Layout, Margin, Transform},
+    order::DrawOrder,
+    overlap::{Footprint, Overlap},
     point_set::PointSet,
-    shape::Shape,
+    print::Print,
+    shape::{Context, Shape, context::mix_seed},
+    stream::{self, Stream},
+    symmetry::Symmetry,
+    tile::Repeat,
 };
-use canvas_builder::State;
+use canvas_builder::{IsUnset, SetBackground, State};
 
 /// Represents the image to be rendered
 #[derive(bon::Builder)]
 pub struct Canvas<P: PointSet> {
-    /// The list of [`Shape`] to be rendered, ordered from lowest layer to highest
+    /// The list of [`Layer`] to be rendered, ordered from lowest to highest
     #[builder(field)]
-    pub shapes: Vec<Box<dyn Shape<Index = P::Index>>>,
+    pub layers: Vec<Layer<P::Index>>,
 
     /// The size of the canvas, in pixels
     #[builder(with = |x: f64, y: f64| (x, y))]
     pub size: (f64, f64),
 
-    /// The image background [`Color`]
-    pub background_color: Color,
+    /// The [`Background`] drawn behind the shapes, such as a [`Color`]
+    #[builder(into)]
+    pub background: Background,
 
     // /// The [`PointSet`] used for the image
     pub points: P,
+
+    /// How the points are scaled to the canvas
+    ///
+    /// By default, the points are kept at their own scale.
+    #[builder(default)]
+    pub fit: Fit,
+
+    /// The space left empty around the edges of the canvas
+    #[builder(default, into)]
+    pub margin: Margin,
+
+    /// Where the points are placed on the canvas
+    #[builder(default)]
+    pub anchor: Anchor,
+
+    /// Whether shapes are scaled along with the points when they are fit to the canvas
+    #[builder(default)]
+    pub scale_shapes: bool,
+
+    /// A transform of the layout of the points, applied around their center
+    #[builder(default)]
+    pub transform: Transform,
+
+    /// Whether the points are resized to cover the canvas once they are transformed
+    ///
+    /// This only changes point sets that can be resized with [`PointSet::resize`], such as a
+    /// [`Lattice`](crate::point_set::lattice::Lattice), so that no corners are left empty. The
+    /// points are resized at their own scale, and are then placed at the scale that `fit` gives
+    /// the points before they are resized. Data found from the points, such as
+    /// [`Packing`](crate::point_set::packing::Packing) sizes, should be found from the
+    /// [`Canvas::resized_points`].
+    #[builder(default)]
+    pub fill: bool,
+
+    /// The seed used for randomness in the shapes
+    ///
+    /// A random seed is used by default. Setting it makes randomness driven by the
+    /// [`Context`] of each shape reproducible.
+    #[builder(default = rand::random())]
+    pub seed: u64,
+
+    /// How the canvas is laid out for printing at a physical size, with any bleed and marks
+    pub print: Option<Print>,
+
+    /// How the shapes are grouped in the SVG document
+    #[builder(default)]
+    pub grouping: Grouping,
+
+    /// The order the points are drawn in, from bottom to top
+    #[builder(default)]
+    pub draw_order: DrawOrder<P::Index>,
+
+    /// The repeat the canvas is a seamless tile of, if any
+    ///
+    /// Shapes crossing an edge of the canvas are drawn again on the opposite edge. The points are
+    /// made to tile the canvas with [`PointSet::tile`] where they can be, and are kept at their
+    /// own scale, so `fit`, `margin`, `transform` and `fill` are ignored. Data found from the
+    /// points should be found from the [`Canvas::resized_points`]. Overlaps are resolved
+    /// for each copy of a shape on its own, so they may not match across the edges.
+    pub repeat: Option<Repeat>,
+
+    /// The symmetry of the canvas about its center, if any
+    ///
+    /// Only the points in one part of the canvas are drawn, and their shapes are copied onto the
+    /// rest of the canvas. Overlaps are resolved for each copy of a shape on its own.
+    pub symmetry: Option<Symmetry>,
+}
+
+/// How the shapes are grouped in the SVG document
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Grouping {
+    /// A group for each point, containing the shapes of every layer at that point
+    #[default]
+    ByPoint,
+
+    /// A group for each [`Layer`], containing its shapes at every point
+    ///
+    /// The groups are marked as layers for Inkscape, and their ids are used as layer names by
+    /// Illustrator, so layers can be edited or separated by color afterwards.
+    ByLayer,
 }
 
 impl<P: PointSet> Canvas<P> {
-    pub fn new(size: (f64, f64), background_color: Color, points: P) -> Self {
+    pub fn new(size: (f64, f64), background: impl Into<Background>, points: P) -> Self {
         Self {
             size,
-            background_color,
+            background: background.into(),
             points,
-            shapes: Vec::new(),
+            fit: Fit::None,
+            margin: Margin::default(),
+            anchor: Anchor::Center,
+            scale_shapes: false,
+            transform: Transform::new(),
+            fill: false,
+            seed: rand::random(),
+            print: None,
+            grouping: Grouping::ByPoint,
+            draw_order: DrawOrder::Index,
+            repeat: None,
+            symmetry: None,
+            layers: Vec::new(),
         }
     }
 
@@ -44,78 +149,727 @@ impl<P: PointSet> Canvas<P> {
     /// `index_filter` can be used to only render the shapes at a given `Index` if it returns
     /// `true`.
     pub fn render(&self, index_filter: impl Fn(&P::Index) -> bool) -> Document {
-        let mut document = Document::new()
-            .set("viewBox", (0, 0, self.size.0, self.size.1))
-            .set("width", self.size.0)
-            .set("height", self.size.1);
+        let mut elements = self.render_elements(&index_filter);
+        elements.extend(self.render_marks());
 
-        let background = self.render_background();
-        document = document.add(background);
+        // The definitions come last, as they can be referenced before they appear
+        let definitions = take_all_definitions(&mut elements);
+        let document = elements
+            .into_iter()
+            .fold(self.document(), |document, element| document.add(element));
 
-        let bb = self.points.bounding_box();
-        let grid_offset = (Coordinate::Cartesian {
-            x: self.size.0,
-            y: self.size.1,
-        } - bb.span())
-            / 2.0;
+        if definitions.is_empty() {
+            document
+        } else {
+            document.add(definitions_element(definitions))
+        }
+    }
+
+    /// Render the SVG document straight to `writer`, writing the shapes as they are drawn
+    ///
+    /// The output is the same as writing the document from [`Canvas::render`], but the document
+    /// isn't kept in memory, which suits canvases with a very large number of points. Only the
+    /// definitions, such as filters, are kept until the end, where they are written. Drawing
+    /// the points in an order other than [`DrawOrder::Index`], or in layers that resolve
+    /// overlaps, still keeps some data for every point. Wrap `writer` in a
+    /// [`BufWriter`](std::io::BufWriter) when writing to a file.
+    pub fn render_to_writer(
+        &self,
+        writer: impl io::Write,
+        index_filter: impl Fn(&P::Index) -> bool,
+    ) -> io::Result<()> {
+        let resized = self.resized_points();
+        let points = resized.as_ref().unwrap_or(&self.points);
 
-        for index in self.points.index_iter().filter(index_filter) {
-            let coordinate = self.points.index_to_coordinate(&index);
-            let offset = grid_offset + coordinate - bb.min();
+        // The definitions are gathered into `defs` at the end, so they are left out of the rest
+        let mut definitions = BTreeMap::new();
+        let mut without_definitions = |mut node: Element| {
+            take_definitions(&mut node, &mut definitions);
+            node
+        };
 
-            if let Some(group) = self.render_shape_group(&index, &offset) {
-                document = document.add(group);
+        let mut stream = Stream::new(writer);
+        let document = self.document();
+        stream.open(document.clone().add(stream::marker()), document);
+        self.render_parts(points, &index_filter, &mut |part| match part {
+            Part::Element(element) => stream.write(&without_definitions(element)),
+            Part::LayerStart(layer, position) => {
+                stream.open(
+                    without_definitions(
+                        self.wrap_layer(layer, position, Group::new().add(stream::marker()))
+                            .into(),
+                    ),
+                    without_definitions(self.wrap_layer(layer, position, Group::new()).into()),
+                );
+                Ok(())
             }
+            Part::LayerEnd => stream.close(),
+        })?;
+        for mark in self.render_marks() {
+            stream.write(&without_definitions(mark))?;
         }
 
-        document
+        if !definitions.is_empty() {
+            stream.write(&definitions_element(definitions.into_values().collect()).into())?;
+        }
+
+        stream.finish()
     }
 
-    /// Add a shape on top of the `shapes` vec
-    pub fn add_shape(&mut self, shape: impl Shape<Index = P::Index> + 'static) {
-        self.shapes.push(Box::new(shape));
+    /// Render a preview sheet of the canvas repeated 3×3 as a tile, to check that it is seamless
+    ///
+    /// The tiles are placed with the `repeat` of the canvas, or a straight repeat if it has none.
+    /// Any print settings are left out of the preview.
+    pub fn render_preview(&self, index_filter: impl Fn(&P::Index) -> bool) -> Document {
+        let (width, height) = self.size;
+        let mut elements = self.render_elements(&index_filter);
+        let definitions = take_all_definitions(&mut elements);
+
+        // Shapes crossing the edges are already drawn on both sides, so each tile is clipped
+        let clip_path = ClipPath::new()
+            .set("id", "tile-clip")
+            .add(Rectangle::new().set("width", width).set("height", height));
+        let tile = elements.into_iter().fold(
+            Group::new()
+                .set("id", "tile")
+                .set("clip-path", "url(#tile-clip)"),
+            |group, element| group.add(element),
+        );
+        let definitions = definitions_element(definitions).add(clip_path).add(tile);
+
+        // Shifted repeats need tiles from outside the sheet to cover its edges
+        self.repeat
+            .unwrap_or_default()
+            .tiles(self.size, -1..=3, -1..=3)
+            .filter(|offset| {
+                let (x, y) = offset.to_cartesian();
+                -width < x && x < 3.0 * width && -height < y && y < 3.0 * height
+            })
+            .fold(
+                Document::new()
+                    .set("viewBox", (0, 0, 3.0 * width, 3.0 * height))
+                    .set("width", 3.0 * width)
+                    .set("height", 3.0 * height)
+                    .add(definitions),
+                |document, offset| {
+                    let (x, y) = offset.to_rounded_cartesian(3);
+                    document.add(Use::new().set("href", "#tile").set("x", x).set("y", y))
+                },
+            )
     }
 
-    fn render_background(&self) -> Rectangle {
-        Rectangle::new()
-            .set("width", self.size.0)
-            .set("height", self.size.1)
-            .set("fill", self.background_color.to_svg_color())
-            .set(
-                "fill-opacity",
-                format!("{:.3}", self.background_color.to_opacity_percent()),
+    /// The document, with its size and namespaces, before anything is added to it
+    fn document(&self) -> Document {
+        let document = match self.print {
+            Some(print) => {
+                // The document grows to fit the bleed and marks around the canvas, while the
+                // `viewBox` keeps the canvas at the origin
+                let round = |length: f64| (length * 1000.0).round() / 1000.0;
+                let border = print.border_px();
+                let (width, height) = (self.size.0 + 2.0 * border, self.size.1 + 2.0 * border);
+                Document::new()
+                    .set(
+                        "viewBox",
+                        (round(-border), round(-border), round(width), round(height)),
+                    )
+                    .set("width", print.to_length(width))
+                    .set("height", print.to_length(height))
+            }
+            None => Document::new()
+                .set("viewBox", (0, 0, self.size.0, self.size.1))
+                .set("width", self.size.0)
+                .set("height", self.size.1),
+        };
+
+        if self.grouping == Grouping::ByLayer {
+            document.set(
+                "xmlns:inkscape",
+                "http://www.inkscape.org/namespaces/inkscape",
             )
+        } else {
+            document
+        }
+    }
+
+    /// The print marks drawn on top of everything else, if any
+    fn render_marks(&self) -> Vec<Element> {
+        self.print
+            .and_then(|print| print.render_marks(self.size))
+            .map(Element::from)
+            .into_iter()
+            .collect()
+    }
+
+    /// Render the background and every layer, leaving definitions inside the elements using them
+    fn render_elements(&self, index_filter: &impl Fn(&P::Index) -> bool) -> Vec<Element> {
+        let resized = self.resized_points();
+        let points = resized.as_ref().unwrap_or(&self.points);
+
+        let mut elements = Vec::new();
+        let mut open_layer = None;
+        let Ok(()) = self.render_parts(points, index_filter, &mut |part| {
+            match part {
+                Part::Element(element) => match &mut open_layer {
+                    Some((_, _, group)) => Node::append(group, element),
+                    None => elements.push(element),
+                },
+                Part::LayerStart(layer, position) => {
+                    open_layer = Some((layer, position, Group::new()));
+                }
+                Part::LayerEnd => {
+                    if let Some((layer, position, group)) = open_layer.take() {
+                        elements.push(self.wrap_layer(layer, position, group).into());
+                    }
+                }
+            }
+            Ok::<_, Infallible>(())
+        });
+
+        elements
+    }
+
+    /// Render the background and every layer with `points`, passing each part to `emit` in the
+    /// order they appear in the document
+    fn render_parts<'a, E>(
+        &'a self,
+        points: &P,
+        index_filter: &impl Fn(&P::Index) -> bool,
+        emit: &mut impl FnMut(Part<'a, P::Index>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if let Some(background) = self.background.render(self.drawn_bb()) {
+            emit(Part::Element(background))?;
+        }
+
+        // The footprints of shapes drawn in layers that resolve overlaps
+        let mut footprints = Vec::new();
+
+        let layout = self.layout(points);
+        let shape_scale = if self.scale_shapes { layout.scale } else { 1.0 };
+
+        let replicas = self.replicas();
+        let center = Coordinate::Cartesian {
+            x: self.size.0 / 2.0,
+            y: self.size.1 / 2.0,
+        };
+
+        for layers in self.layer_runs() {
+            let layer = &self.layers[layers.start];
+            let is_grouped = self.grouping == Grouping::ByLayer || layer.is_drawn_whole();
+            if is_grouped {
+                emit(Part::LayerStart(layer, layers.start))?;
+            }
+
+            let contexts = self
+                .contexts(points, index_filter, layout, shape_scale)
+                .filter(|context| {
+                    self.symmetry
+                        .is_none_or(|symmetry| symmetry.contains(context.coordinate - center))
+                });
+            for mut context in contexts {
+                for replica in &replicas {
+                    if let Some(group) = self.render_shape_group(
+                        &mut context,
+                        *replica,
+                        layers.clone(),
+                        shape_scale,
+                        &mut footprints,
+                    ) {
+                        emit(Part::Element(group.into()))?;
+                    }
+                }
+            }
+
+            if is_grouped {
+                emit(Part::LayerEnd)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Wrap `group`, containing the whole `layer` at `position`, with the settings of the layer
+    fn wrap_layer(&self, layer: &Layer<P::Index>, position: usize, mut group: Group) -> Group {
+        if layer.is_drawn_whole() {
+            group = layer.apply(group, self.drawn_bb());
+        }
+        if self.grouping == Grouping::ByLayer {
+            group = layer.label(group, position);
+        }
+
+        group
+    }
+
+    /// Add a shape on top of the `layers` vec
+    pub fn add_shape(&mut self, shape: impl Shape<Index = P::Index> + 'static) {
+        self.layers.push(Layer::new(shape));
+    }
+
+    /// Add a layer on top of the `layers` vec
+    pub fn add_layer(&mut self, layer: Layer<P::Index>) {
+        self.layers.push(layer);
+    }
+
+    /// The area that is drawn on, which includes any bleed around the canvas
+    fn drawn_bb(&self) -> BoundingBox {
+        let canvas_bb = BoundingBox::from_point(Coordinate::Cartesian {
+            x: self.size.0,
+            y: self.size.1,
+        });
+        self.print
+            .map_or(canvas_bb, |print| print.bleed_bb(self.size))
+    }
+
+    /// Find where the points are placed on the canvas
+    ///
+    /// Tiles keep the points at their own scale, so that they repeat at the size of the canvas.
+    fn layout(&self, points: &P) -> Layout {
+        match self.repeat {
+            Some(_) => Layout::new(
+                points.bounding_box(),
+                self.size,
+                Fit::None,
+                Margin::default(),
+                self.anchor,
+                Transform::new(),
+            ),
+            None => {
+                // Points resized to fill the canvas keep the scale of the points they were
+                // resized from, as they were resized for that scale
+                let scale = Layout::fit_scale(
+                    self.points.bounding_box(),
+                    self.size,
+                    self.fit,
+                    self.margin,
+                    self.transform,
+                );
+                Layout::with_scale(
+                    points.bounding_box(),
+                    self.size,
+                    scale,
+                    self.margin,
+                    self.anchor,
+                    self.transform,
+                )
+            }
+        }
+    }
+
+    /// The points the canvas draws when it tiles them for its `repeat` or resizes them to `fill`
+    /// it, or `None` when it draws its `points` as they are
+    ///
+    /// Data found from the points, such as [`Packing`](crate::point_set::packing::Packing) sizes
+    /// or [`Connector`](crate::shape::Connector) edges, should be found from these points, as
+    /// their indices and spacing can differ from the original points.
+    pub fn resized_points(&self) -> Option<P> {
+        match self.repeat {
+            Some(repeat) => self.points.tile(self.size.0, self.size.1, repeat),
+            None if self.fill => self.resize_points(),
+            None => None,
+        }
+    }
+
+    /// Resize the points to cover the area inside the margins once they are transformed and fit
+    ///
+    /// Sides without a margin are covered out to the edge of any bleed.
+    fn resize_points(&self) -> Option<P> {
+        let scale = Layout::fit_scale(
+            self.points.bounding_box(),
+            self.size,
+            self.fit,
+            self.margin,
+            self.transform,
+        );
+        let bleed = self.print.map_or(0.0, |print| print.bleed_px());
+        let extend = |margin: f64| if margin == 0.0 { bleed } else { -margin };
+
+        let (width, height) = self.transform.cover(
+            self.size.0 + extend(self.margin.left) + extend(self.margin.right),
+            self.size.1 + extend(self.margin.top) + extend(self.margin.bottom),
+        );
+
+        self.points.resize(width / scale, height / scale)
     }
 
-    fn render_shape_group(&self, index: &P::Index, offset: &Coordinate) -> Option<Group> {
+    /// The copies of the shapes at each point, starting with the shapes themselves
+    ///
+    /// Shapes are copied around the center for the symmetry of the canvas, then each copy is drawn
+    /// again from each neighboring tile so that it continues across the edges.
+    fn replicas(&self) -> Vec<Replica> {
+        let transforms = self
+            .symmetry
+            .map_or_else(|| vec![Transform::new()], |symmetry| symmetry.transforms());
+        let offsets: Vec<Coordinate> = match self.repeat {
+            Some(repeat) => repeat.neighbors(self.size).collect(),
+            None => vec![Coordinate::origin()],
+        };
+
+        transforms
+            .into_iter()
+            .flat_map(|transform| {
+                offsets
+                    .iter()
+                    .map(move |&offset| Replica { transform, offset })
+            })
+            .collect()
+    }
+
+    /// Iterate through the context of every point that passes `index_filter`, in the draw order
+    fn contexts(
+        &self,
+        points: &P,
+        index_filter: &impl Fn(&P::Index) -> bool,
+        layout: Layout,
+        shape_scale: f64,
+    ) -> impl Iterator<Item = Context<P::Index>> {
+        let count = points.count();
+        let vector_transform = layout.vector_transform(shape_scale);
+
+        let contexts = points.index_iter().filter(index_filter).map(move |index| {
+            let coordinate = points.index_to_coordinate(&index);
+            let offset = layout.place(coordinate);
+
+            // Seeding from the coordinate keeps random choices stable for each point,
+            // regardless of the order of the points or which are filtered out
+            let (x, y) = coordinate.to_cartesian();
+            let seed = mix_seed(mix_seed(self.seed, x.to_bits()), y.to_bits());
+            let mut context = Context::new(index, offset, self.size, count, seed);
+            context.layout = vector_transform;
+            context
+        });
+
+        // Points drawn in the order of their indices are passed on as they come, without keeping
+        // every context
+        match self.draw_order {
+            DrawOrder::Index => Either::Left(contexts),
+            _ => Either::Right(self.draw_order.sort(contexts.collect()).into_iter()),
+        }
+    }
+
+    /// Split the layers into runs that are drawn point by point
+    ///
+    /// Consecutive plain layers are drawn together at each point, while each layer with effects,
+    /// blending or opacity is drawn on its own. When grouping by layer, every layer is drawn on its
+    /// own.
+    fn layer_runs(&self) -> Vec<Range<usize>> {
+        if self.grouping == Grouping::ByLayer {
+            return (0..self.layers.len()).map(|i| i..i + 1).collect();
+        }
+
+        let mut runs: Vec<Range<usize>> = Vec::new();
+
+        for (i, layer) in self.layers.iter().enumerate() {
+            match runs.last_mut() {
+                Some(run)
+                    if !layer.is_drawn_whole() && !self.layers[run.start].is_drawn_whole() =>
+                {
+                    run.end = i + 1;
+                }
+                _ => runs.push(i..i + 1),
+            }
+        }
+
+        runs
+    }
+
+    /// Render the shapes of `layers` at the point of `context`, copied by `replica`
+    fn render_shape_group(
+        &self,
+        context: &mut Context<P::Index>,
+        replica: Replica,
+        layers: Range<usize>,
+        shape_scale: f64,
+        footprints: &mut Vec<Footprint>,
+    ) -> Option<Group> {
+        let offset = replica.place(context.coordinate, context.center());
         let (offset_x, offset_y) = offset.to_cartesian();
+        let point_seed = context.seed;
+
+        // Whether a shape with the bounding box `bb` at the point is on the drawn area
+        let drawn_bb = self.drawn_bb() - offset;
+        let is_drawn = |bb: BoundingBox| {
+            bb.map(|point| replica.transform.apply(point * shape_scale))
+                .intersects(&drawn_bb)
+        };
 
-        let paths = &mut self
-            .shapes
+        let paths = &mut self.layers[layers.clone()]
             .iter()
-            .filter_map(|shape| {
-                let (path, bb) = shape.generate_path_and_bb(&index);
-                if bb.intersects(
-                    &(BoundingBox::from_point(Coordinate::Cartesian {
-                        x: self.size.0,
-                        y: self.size.1,
-                    }) - *offset),
-                ) {
+            .zip(layers)
+            .filter_map(|(layer, i)| {
+                // Each layer makes its own random choices
+                context.seed = mix_seed(point_seed, i as u64);
+
+                // Shapes whose bounds are known are skipped before they are generated if they
+                // miss the canvas
+                if layer.shape.bounds(context).is_some_and(|bb| !is_drawn(bb)) {
+                    return None;
+                }
+                if layer.overlap == Overlap::Allow {
+                    let (element, bb) = layer.shape.generate_element_and_bb(context);
+                    return is_drawn(bb).then_some(element);
+                }
+
+                let (outline, path, bb) = layer.shape.generate_path_element_and_bb(context);
+                if !is_drawn(bb) {
+                    return None;
+                }
+
+                // Place the shape at the first scale and offset that doesn't overlap, where the
+                // first placement leaves the shape as it is
+                let footprint = Footprint::from_path(&outline)
+                    .grow_to(bb)
+                    .apply(&replica.transform);
+                let (attempt, (scale, nudge), footprint) =
+                    layer
+                        .overlap
+                        .placements()
+                        .into_iter()
+                        .enumerate()
+                        .find_map(|(attempt, (scale, nudge))| {
+                            let footprint = footprint.transform(
+                                scale * shape_scale,
+                                offset + replica.transform.apply(nudge) * shape_scale,
+                            );
+                            (!footprints.iter().any(|other| other.overlaps(&footprint)))
+                                .then_some((attempt, (scale, nudge), footprint))
+                        })?;
+                footprints.push(footprint);
+
+                if attempt == 0 {
                     Some(path)
                 } else {
-                    None
+                    // The same transform as the footprint, which scales and then nudges the shape
+                    let (nudge_x, nudge_y) = nudge.to_rounded_cartesian(3);
+                    let transform = format!("translate({nudge_x},{nudge_y}) scale({scale:.3})");
+                    Some(Group::new().set("transform", transform).add(path).into())
                 }
             })
             .peekable();
 
-        if paths.peek().is_some() {
-            Some(paths.fold(Group::new(), |group, path| group.add(path)).set(
-                "transform",
-                format!("translate({offset_x:.3},{offset_y:.3})"),
-            ))
+        let group = if paths.peek().is_some() {
+            let mut transform = format!("translate({offset_x:.3},{offset_y:.3})");
+            if !replica.transform.is_identity() {
+                transform = format!("{transform} {}", replica.transform.to_svg());
+            }
+            if shape_scale != 1.0 {
+                transform = format!("{transform} scale({shape_scale:.3})");
+            }
+            Some(
+                paths
+                    .fold(Group::new(), |group, path| group.add(path))
+                    .set("transform", transform),
+            )
         } else {
             None
+        };
+
+        // Copies in other tiles make the same random choices
+        context.seed = point_seed;
+        group
+    }
+}
+
+/// A copy of the shapes drawn at every point, for the symmetry and tiles of the [`Canvas`]
+#[derive(Debug, Clone, Copy)]
+struct Replica {
+    /// The rotation or mirroring of the copy around the center of the canvas
+    transform: Transform,
+
+    /// How far the copy is moved once it is transformed
+    offset: Coordinate,
+}
+
+impl Replica {
+    /// Where the copy of a point at `coordinate` is placed, for a canvas with its center at
+    /// `center`
+    fn place(&self, coordinate: Coordinate, center: Coordinate) -> Coordinate {
+        center + self.transform.apply(coordinate - center) + self.offset
+    }
+}
+
+/// A [`Shape`] drawn at every point of the [`Canvas`], with settings for the whole layer
+///
+/// A layer with effects, blending, opacity or isolation is drawn as a single group, so that the
+/// settings apply to the layer as a whole. For example, shapes within a layer with an opacity
+/// don't show through each other, and effects such as a fabric grain are continuous across the
+/// canvas. Its shapes are drawn above those of lower layers at every point, rather than point by
+/// point.
+pub struct Layer<I> {
+    /// The shape drawn at each point
+    pub shape: Box<dyn Shape<Index = I>>,
+
+    /// The list of [`Effect`] applied to the whole layer, in order
+    pub effects: Vec<Effect>,
+
+    /// How the layer is blended with the layers below it
+    pub blend_mode: BlendMode,
+
+    /// The opacity of the whole layer, from `0.0` to `1.0`
+    pub opacity: f64,
+
+    /// Whether blend modes of shapes within the layer only mix with each other
+    pub isolation: bool,
+
+    /// How shapes in the layer that overlap shapes drawn before them are resolved
+    pub overlap: Overlap,
+
+    /// The name of the layer, used when grouping by layer
+    ///
+    /// Layers are named `Layer 1`, `Layer 2`, and so on by default.
+    pub name: Option<String>,
+
+    /// The id of the layer group, used when grouping by layer
+    ///
+    /// Layers have the ids `layer-1`, `layer-2`, and so on by default.
+    pub id: Option<String>,
+}
+
+impl<I> Layer<I> {
+    /// Define a new layer drawing `shape`
+    pub fn new(shape: impl Shape<Index = I> + 'static) -> Self {
+        Self {
+            shape: Box::new(shape),
+            effects: Vec::new(),
+            blend_mode: BlendMode::Normal,
+            opacity: 1.0,
+            isolation: false,
+            overlap: Overlap::Allow,
+            name: None,
+            id: None,
+        }
+    }
+
+    /// Add an [`Effect`] to the whole layer
+    pub fn effect(mut self, effect: Effect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    /// Set how the layer is blended with the layers below it
+    pub fn blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    /// Set the opacity of the whole layer
+    pub fn opacity(mut self, opacity: f64) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Isolate the layer, so that blend modes of shapes within it only mix with each other
+    pub fn isolate(mut self) -> Self {
+        self.isolation = true;
+        self
+    }
+
+    /// Set how shapes in the layer that overlap shapes drawn before them are resolved
+    ///
+    /// Shapes are drawn point by point, so earlier points and lower layers keep their place.
+    pub fn overlap(mut self, overlap: Overlap) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Set the name of the layer, used when grouping by layer
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the id of the layer group, used when grouping by layer
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Mark `group`, containing the whole layer at `position` in the canvas, as an Inkscape layer
+    fn label(&self, group: Group, position: usize) -> Group {
+        let name = self
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Layer {}", position + 1));
+        let id = self
+            .id
+            .clone()
+            .unwrap_or_else(|| format!("layer-{}", position + 1));
+
+        group
+            .set("id", id)
+            .set("inkscape:groupmode", "layer")
+            .set("inkscape:label", name)
+    }
+
+    /// Whether the layer needs to be drawn as a single group
+    fn is_drawn_whole(&self) -> bool {
+        !self.effects.is_empty()
+            || self.blend_mode != BlendMode::Normal
+            || self.opacity < 1.0
+            || self.isolation
+    }
+
+    /// Apply the settings of the layer to `group`, which contains the whole layer
+    fn apply(&self, group: Group, canvas_bb: BoundingBox) -> Group {
+        let mut group = self
+            .effects
+            .iter()
+            .fold(group, |group, effect| effect.apply(group, canvas_bb));
+
+        if self.opacity < 1.0 {
+            group = group.set("opacity", format!("{:.3}", self.opacity));
+        }
+
+        let mut style = Vec::new();
+        if self.blend_mode != BlendMode::Normal {
+            style.push(format!("mix-blend-mode:{}", self.blend_mode.to_css()));
+        }
+        if self.isolation {
+            style.push("isolation:isolate".to_string());
         }
+        if !style.is_empty() {
+            group = group.set("style", style.join(";"));
+        }
+
+        group
+    }
+}
+
+/// A part of the document, passed on in order as the canvas is rendered
+enum Part<'a, I> {
+    /// An element, inside the open layer if there is one
+    Element(Element),
+
+    /// The start of a layer drawn as a single group, with its position in the layers
+    LayerStart(&'a Layer<I>, usize),
+
+    /// The end of the open layer
+    LayerEnd,
+}
+
+/// Take the definitions out of `elements`, such as filters and gradients, to be added to `defs`
+fn take_all_definitions(elements: &mut [Element]) -> Vec<Box<dyn Node>> {
+    let mut definitions = BTreeMap::new();
+    for element in elements {
+        take_definitions(element, &mut definitions);
+    }
+
+    definitions.into_values().collect()
+}
+
+/// Gather `definitions` into a `defs` element
+fn definitions_element(definitions: Vec<Box<dyn Node>>) -> Definitions {
+    definitions
+        .into_iter()
+        .fold(Definitions::new(), |element, definition| {
+            element.add(definition)
+        })
+}
+
+impl<P: PointSet> Pattern for Canvas<P> {
+    /// Render every point of the canvas, with its background, as a group
+    fn render_pattern(&self) -> Group {
+        self.render_elements(&|_| true)
+            .into_iter()
+            .fold(Group::new(), |group, element| group.add(element))
     }
 }
 
@@ -124,8 +878,193 @@ where
     P: PointSet,
     S: State,
 {
+    /// Set the background to a single [`Color`]
+    pub fn background_color(self, color: Color) -> CanvasBuilder<P, SetBackground<S>>
+    where
+        S::Background: IsUnset,
+    {
+        self.background(color)
+    }
+
     pub fn add_shape(&mut self, shape: impl Shape<Index = P::Index> + 'static) -> &mut Self {
-        self.shapes.push(Box::new(shape));
+        self.layers.push(Layer::new(shape));
         self
     }
+
+    pub fn add_layer(&mut self, layer: Layer<P::Index>) -> &mut Self {
+        self.layers.push(layer);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geometry::Angle,
+        point_set::{
+            lattice::{Index, Lattice},
+            packing::Packing,
+        },
+        shape::{Polygon, ShapeExt},
+    };
+
+    fn canvas(grouping: Grouping, draw_order: DrawOrder<Index>) -> Canvas<Lattice> {
+        let points = Lattice::square_builder()
+            .grid_size(4, 4)
+            .len_a(20.0)
+            .build();
+        let mut builder = Canvas::builder()
+            .size(100.0, 100.0)
+            .background_color(Color::new(255, 255, 255, 255))
+            .points(points)
+            .seed(7)
+            .grouping(grouping)
+            .draw_order(draw_order);
+
+        // The wobble and the colors are random, and the blur adds a definition
+        builder.add_shape(
+            Polygon::builder()
+                .sides(6)
+                .size(12.0)
+                .cv(0.1)
+                .color(Color::new(0, 0, 0, 255))
+                .build()
+                .effect(Effect::Blur { std_dev: 1.0 }),
+        );
+        builder.add_layer(
+            Layer::new(
+                Polygon::builder()
+                    .sides(4)
+                    .size(6.0)
+                    .color(Color::new(200, 0, 0, 255))
+                    .build(),
+            )
+            .effect(Effect::Blur { std_dev: 2.0 }),
+        );
+
+        builder.build()
+    }
+
+    #[test]
+    fn packings_of_the_resized_points_size_every_drawn_point() {
+        for repeat in [None, Some(Repeat::Brick)] {
+            let mut canvas = Canvas::builder()
+                .size(100.0, 100.0)
+                .background_color(Color::new(255, 255, 255, 255))
+                .points(
+                    Lattice::square_builder()
+                        .grid_size(2, 2)
+                        .len_a(20.0)
+                        .build(),
+                )
+                .fill(true)
+                .maybe_repeat(repeat)
+                .seed(7)
+                .build();
+
+            let resized = canvas.resized_points().unwrap();
+            assert!(resized.count() > canvas.points.count());
+
+            let packing = Packing::builder().points(&resized).build();
+            canvas.add_shape(
+                Polygon::builder()
+                    .sides(4)
+                    .size_context_fn(packing.size_context_fn())
+                    .color(Color::new(0, 0, 0, 255))
+                    .build(),
+            );
+            canvas.render(|_| true);
+        }
+    }
+
+    #[test]
+    fn placed_shapes_are_scaled_and_nudged_like_their_footprints() {
+        for (overlap, transform) in [
+            (
+                Overlap::Shrink { min_scale: 0.1 },
+                "translate(0,0) scale(0.",
+            ),
+            (Overlap::Nudge { distance: 40.0 }, ") scale(1.000)"),
+        ] {
+            let mut canvas = Canvas::builder()
+                .size(100.0, 100.0)
+                .background_color(Color::new(255, 255, 255, 255))
+                .points(Lattice::square_builder().grid_size(2, 1).len_a(8.0).build())
+                .fit(Fit::None)
+                .seed(7)
+                .build();
+            canvas.add_layer(
+                Layer::new(
+                    Polygon::builder()
+                        .sides(4)
+                        .size(10.0)
+                        .color(Color::new(0, 0, 0, 255))
+                        .build(),
+                )
+                .overlap(overlap),
+            );
+
+            let document = canvas.render(|_| true).to_string();
+            assert!(document.contains(transform), "{document}");
+        }
+    }
+
+    #[test]
+    fn render_to_writer_matches_render() {
+        for grouping in [Grouping::ByPoint, Grouping::ByLayer] {
+            for draw_order in [DrawOrder::Index, DrawOrder::Random(3)] {
+                let canvas = canvas(grouping, draw_order);
+
+                let mut output = Vec::new();
+                canvas.render_to_writer(&mut output, |_| true).unwrap();
+
+                assert_eq!(
+                    String::from_utf8(output).unwrap(),
+                    canvas.render(|_| true).to_string()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn definitions_come_last() {
+        let document = canvas(Grouping::ByPoint, DrawOrder::Index)
+            .render(|_| true)
+            .to_string();
+
+        assert!(document.contains("url(#"));
+        assert!(document.trim_end().ends_with("</defs>\n</svg>"));
+    }
+
+    #[test]
+    fn layout_maps_vectors_between_points_as_they_are_drawn() {
+        for scale_shapes in [false, true] {
+            let canvas = Canvas::builder()
+                .size(300.0, 200.0)
+                .background_color(Color::new(255, 255, 255, 255))
+                .points(
+                    Lattice::square_builder()
+                        .grid_size(3, 3)
+                        .len_a(10.0)
+                        .build(),
+                )
+                .fit(Fit::Contain)
+                .transform(Transform::new().rotate(Angle::Degree(30.0)).scale(1.0, 0.5))
+                .scale_shapes(scale_shapes)
+                .build();
+
+            let layout = canvas.layout(&canvas.points);
+            let shape_scale = if scale_shapes { layout.scale } else { 1.0 };
+            let contexts: Vec<_> = canvas
+                .contexts(&canvas.points, &|_| true, layout, shape_scale)
+                .collect();
+
+            let (from, to) = (&contexts[0], &contexts[4]);
+            let vector = canvas.points.index_to_coordinate(&to.index)
+                - canvas.points.index_to_coordinate(&from.index);
+            let drawn = (to.coordinate - from.coordinate) / shape_scale;
+            assert!(from.layout.apply(vector).dist(&drawn) < 1e-9);
+        }
+    }
 }
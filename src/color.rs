@@ -17,6 +17,18 @@ impl Color {
         Ok(Self(HexColor::parse(hex)?))
     }
 
+    /// Linearly interpolate between two colors, including their opacity
+    pub fn lerp(&self, other: &Self, t: f64) -> Self {
+        let mix = |a: u8, b: u8| (f64::from(a) + t * (f64::from(b) - f64::from(a))).round() as u8;
+
+        Self::new(
+            mix(self.0.r, other.0.r),
+            mix(self.0.g, other.0.g),
+            mix(self.0.b, other.0.b),
+            mix(self.0.a, other.0.a),
+        )
+    }
+
     #[inline]
     pub(crate) fn to_svg_color(self) -> String {
         format!("rgb({},{},{})", self.0.r, self.0.g, self.0.b,)
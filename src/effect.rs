@@ -0,0 +1,320 @@
+//! SVG filter effects, for the feathered and bleeding edges of hand-dyed fabric
+use std::collections::BTreeMap;
+
+use svg::{
+    Node,
+    node::element::{
+        Element, Filter, FilterEffectBlend, FilterEffectColorMatrix, FilterEffectComposite,
+        FilterEffectDisplacementMap, FilterEffectDropShadow, FilterEffectGaussianBlur,
+        FilterEffectTurbulence, Group,
+    },
+};
+
+use crate::{
+    Color,
+    geometry::{BoundingBox, Coordinate},
+};
+
+/// An effect applied to a shape or a whole layer with an SVG `filter`
+///
+/// Shapes opt into an effect with [`ShapeExt::effect`](crate::shape::ShapeExt::effect), and whole
+/// layers with [`Layer::effect`](crate::Layer::effect).
+#[derive(Debug, Clone, Copy)]
+pub enum Effect {
+    /// A Gaussian blur, for soft and feathered edges
+    Blur { std_dev: f64 },
+
+    /// Ragged edges, made by displacing the shape with turbulence
+    ///
+    /// `scale` is the largest distance that the edge is moved, and `frequency` controls how fine
+    /// the raggedness is. Changing the `seed` gives a different pattern.
+    Ragged {
+        frequency: f64,
+        octaves: u8,
+        scale: f64,
+        seed: u32,
+    },
+
+    /// A fabric grain texture, darkening the shape by up to `intensity` from `0.0` to `1.0`
+    ///
+    /// Using a different `frequency` along x and y gives the look of woven threads.
+    Grain {
+        frequency: (f64, f64),
+        intensity: f64,
+        seed: u32,
+    },
+
+    /// A drop shadow, offset from the shape and blurred
+    DropShadow {
+        offset: Coordinate,
+        std_dev: f64,
+        color: Color,
+    },
+}
+
+impl Effect {
+    /// How far the effect can draw outside of the bounding box of the shape
+    fn margin(&self) -> f64 {
+        match *self {
+            Effect::Blur { std_dev } => 3.0 * std_dev.abs(),
+            Effect::Ragged { scale, .. } => scale.abs() / 2.0,
+            Effect::Grain { .. } => 0.0,
+            Effect::DropShadow {
+                offset, std_dev, ..
+            } => offset.to_polar().0 + 3.0 * std_dev.abs(),
+        }
+    }
+
+    /// Expand a bounding box to the region that the effect can draw in
+    pub fn expand_bb(&self, bb: BoundingBox) -> BoundingBox {
+        if bb.is_empty() {
+            return bb;
+        }
+
+        let margin = Coordinate::Cartesian {
+            x: self.margin(),
+            y: self.margin(),
+        };
+        BoundingBox::new(bb.min() - margin, bb.max() + margin)
+    }
+
+    /// Create the `filter` definition for the effect, covering `region`, along with its id
+    ///
+    /// The id is derived from the definition, so identical filters can share one.
+    pub(crate) fn filter(&self, region: BoundingBox) -> (String, Filter) {
+        let filter = self.filter_body(region);
+        let id = definition_id("effect", &filter.to_string());
+
+        (id.clone(), filter.set("id", id))
+    }
+
+    /// The `filter` definition for the effect covering `region`, without an id
+    fn filter_body(&self, region: BoundingBox) -> Filter {
+        let (x, y) = region.min().to_rounded_cartesian(3);
+        let (width, height) = region.span().to_rounded_cartesian(3);
+
+        let filter = Filter::new()
+            .set("filterUnits", "userSpaceOnUse")
+            .set("x", x)
+            .set("y", y)
+            .set("width", width)
+            .set("height", height);
+
+        match *self {
+            Effect::Blur { std_dev } => {
+                filter.add(FilterEffectGaussianBlur::new().set("stdDeviation", std_dev))
+            }
+            Effect::Ragged {
+                frequency,
+                octaves,
+                scale,
+                seed,
+            } => filter
+                .add(
+                    FilterEffectTurbulence::new()
+                        .set("type", "fractalNoise")
+                        .set("baseFrequency", frequency)
+                        .set("numOctaves", octaves)
+                        .set("seed", seed)
+                        .set("result", "noise"),
+                )
+                .add(
+                    FilterEffectDisplacementMap::new()
+                        .set("in", "SourceGraphic")
+                        .set("in2", "noise")
+                        .set("scale", scale)
+                        .set("xChannelSelector", "R")
+                        .set("yChannelSelector", "G"),
+                ),
+            Effect::Grain {
+                frequency: (frequency_x, frequency_y),
+                intensity,
+                seed,
+            } => {
+                // Map the noise to an opaque gray between `1 - intensity` and white
+                let intensity = intensity.clamp(0.0, 1.0);
+                let gray = format!("{intensity} 0 0 0 {}", 1.0 - intensity);
+
+                filter
+                    .add(
+                        FilterEffectTurbulence::new()
+                            .set("type", "fractalNoise")
+                            .set("baseFrequency", format!("{frequency_x} {frequency_y}"))
+                            .set("numOctaves", 2)
+                            .set("seed", seed)
+                            .set("result", "noise"),
+                    )
+                    .add(
+                        FilterEffectColorMatrix::new()
+                            .set("in", "noise")
+                            .set("type", "matrix")
+                            .set("values", format!("{gray} {gray} {gray} 0 0 0 0 1"))
+                            .set("result", "grain"),
+                    )
+                    .add(
+                        FilterEffectBlend::new()
+                            .set("in", "SourceGraphic")
+                            .set("in2", "grain")
+                            .set("mode", "multiply")
+                            .set("result", "textured"),
+                    )
+                    .add(
+                        FilterEffectComposite::new()
+                            .set("in", "textured")
+                            .set("in2", "SourceGraphic")
+                            .set("operator", "in"),
+                    )
+            }
+            Effect::DropShadow {
+                offset,
+                std_dev,
+                color,
+            } => {
+                let (dx, dy) = offset.to_rounded_cartesian(3);
+                filter.add(
+                    FilterEffectDropShadow::new()
+                        .set("dx", dx)
+                        .set("dy", dy)
+                        .set("stdDeviation", std_dev)
+                        .set("flood-color", color.to_svg_color())
+                        .set("flood-opacity", color.to_opacity_percent()),
+                )
+            }
+        }
+    }
+
+    /// Wrap `element` in a group using the effect, with the `filter` definition alongside it
+    ///
+    /// The definitions are gathered into the `defs` of the document when the
+    /// [`Canvas`](crate::Canvas) is rendered.
+    pub(crate) fn apply(&self, element: impl Into<Element>, region: BoundingBox) -> Group {
+        let (id, filter) = self.filter(self.expand_bb(region));
+
+        Group::new()
+            .set("filter", format!("url(#{id})"))
+            .add(filter)
+            .add(element.into())
+    }
+}
+
+/// An id for an SVG definition, such as a filter or gradient, derived from its `content`
+///
+/// The id uses 64-bit FNV-1a, which unlike the hashers of the standard library is fixed, so the
+/// same definition has the same id in every build.
+pub(crate) fn definition_id(prefix: &str, content: &str) -> String {
+    let hash = content
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+
+    format!("{prefix}-{hash:016x}")
+}
+
+/// How a shape or layer is blended with what is drawn below it, as with CSS `mix-blend-mode`
+///
+/// Blending lets overlapping dye colors mix instead of covering each other.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+impl BlendMode {
+    /// The value of the CSS `mix-blend-mode` property
+    pub(crate) fn to_css(self) -> &'static str {
+        match self {
+            BlendMode::Normal => "normal",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Overlay => "overlay",
+            BlendMode::Darken => "darken",
+            BlendMode::Lighten => "lighten",
+            BlendMode::ColorDodge => "color-dodge",
+            BlendMode::ColorBurn => "color-burn",
+            BlendMode::HardLight => "hard-light",
+            BlendMode::SoftLight => "soft-light",
+            BlendMode::Difference => "difference",
+            BlendMode::Exclusion => "exclusion",
+            BlendMode::Hue => "hue",
+            BlendMode::Saturation => "saturation",
+            BlendMode::Color => "color",
+            BlendMode::Luminosity => "luminosity",
+        }
+    }
+}
+
+/// The elements that are moved into the `defs` of the document
+const DEFINITIONS: [&str; 3] = ["filter", "linearGradient", "radialGradient"];
+
+/// Move every definition below `node`, such as a `filter`, into `definitions`, keyed by id so each
+/// is only defined once
+pub(crate) fn take_definitions(
+    node: &mut dyn Node,
+    definitions: &mut BTreeMap<String, Box<dyn Node>>,
+) {
+    let Some(children) = node.get_children_mut() else {
+        return;
+    };
+
+    let mut i = 0;
+    while i < children.len() {
+        if DEFINITIONS.contains(&children[i].get_name()) {
+            let definition = children.remove(i);
+            let id = definition
+                .get_attributes()
+                .and_then(|attributes| attributes.get("id"))
+                .map(|id| id.to_string())
+                .unwrap_or_default();
+            definitions.entry(id).or_insert(definition);
+        } else {
+            take_definitions(children[i].as_mut(), definitions);
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn definition_ids_are_fixed() {
+        // Known values of 64-bit FNV-1a
+        assert_eq!(definition_id("effect", ""), "effect-cbf29ce484222325");
+        assert_eq!(definition_id("gradient", "a"), "gradient-af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn identical_filters_share_an_id() {
+        let region = BoundingBox::new(
+            Coordinate::origin(),
+            Coordinate::Cartesian { x: 10.0, y: 5.0 },
+        );
+        let moved = BoundingBox::new(
+            Coordinate::Cartesian { x: 1.0, y: 0.0 },
+            Coordinate::Cartesian { x: 11.0, y: 5.0 },
+        );
+        let blur = Effect::Blur { std_dev: 2.0 };
+        let id = |effect: Effect, region| effect.filter(region).0;
+
+        assert_eq!(id(blur, region), id(Effect::Blur { std_dev: 2.0 }, region));
+        assert_ne!(id(blur, region), id(blur, moved));
+        assert_ne!(id(blur, region), id(Effect::Blur { std_dev: 3.0 }, region));
+    }
+}
@@ -0,0 +1,52 @@
+use std::{fmt, io};
+
+/// Errors that can occur when loading external resources
+#[derive(Debug)]
+pub enum Error {
+    /// A file could not be read
+    Io(io::Error),
+
+    /// SVG content could not be parsed
+    Svg(svg::parser::Error),
+
+    /// A font could not be parsed
+    Font(ttf_parser::FaceParsingError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "failed to read file: {error}"),
+            Error::Svg(error) => write!(f, "failed to parse SVG: {error}"),
+            Error::Font(error) => write!(f, "failed to parse font: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Svg(error) => Some(error),
+            Error::Font(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<svg::parser::Error> for Error {
+    fn from(error: svg::parser::Error) -> Self {
+        Error::Svg(error)
+    }
+}
+
+impl From<ttf_parser::FaceParsingError> for Error {
+    fn from(error: ttf_parser::FaceParsingError) -> Self {
+        Error::Font(error)
+    }
+}
@@ -0,0 +1,810 @@
+use std::{f64::consts::PI, io};
+
+use svg::{
+    node::{
+        Attributes,
+        element::{
+            Path,
+            path::{Command, Data, Position},
+            tag::Type,
+        },
+    },
+    parser::Event,
+};
+
+use crate::{
+    Error,
+    geometry::{Angle, BoundingBox, Coordinate},
+};
+
+/// A segment of a [`BezierPath`]
+#[derive(Debug, Clone, Copy)]
+pub enum Segment {
+    /// Start a new contour at the point
+    MoveTo(Coordinate),
+
+    /// A straight line to the point
+    LineTo(Coordinate),
+
+    /// A cubic Bézier curve with two control points, ending at the last point
+    CubicTo(Coordinate, Coordinate, Coordinate),
+
+    /// Close the contour with a straight line to its start
+    Close,
+}
+
+/// How the inside of a path is found where its contours overlap or nest, as in the SVG
+/// `fill-rule` property
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FillRule {
+    /// A point is inside if the contours wind around it a nonzero number of times
+    #[default]
+    NonZero,
+
+    /// A point is inside if it is within an odd number of contours, so nested contours make holes
+    EvenOdd,
+}
+
+impl FillRule {
+    /// The value of the SVG `fill-rule` property
+    pub fn to_svg(self) -> &'static str {
+        match self {
+            FillRule::NonZero => "nonzero",
+            FillRule::EvenOdd => "evenodd",
+        }
+    }
+}
+
+/// A path made up only of absolute moves, straight lines and cubic Bézier curves
+///
+/// Any SVG path data can be converted to this form, which makes it simple to transform. Quadratic
+/// curves are converted to cubic curves exactly, and elliptical arcs are approximated by cubic
+/// curves.
+#[derive(Debug, Clone, Default)]
+pub struct BezierPath {
+    /// The segments of the path
+    pub segments: Vec<Segment>,
+
+    /// The fill rule of the path
+    pub fill_rule: FillRule,
+}
+
+impl BezierPath {
+    /// Create a new path
+    pub fn new(segments: Vec<Segment>) -> Self {
+        Self {
+            segments,
+            fill_rule: FillRule::default(),
+        }
+    }
+
+    /// Set the fill rule of the path
+    pub fn with_fill_rule(self, fill_rule: FillRule) -> Self {
+        Self { fill_rule, ..self }
+    }
+
+    /// Parse SVG path data, as found in the `d` attribute of a `path` element
+    pub fn parse(d: &str) -> Result<Self, Error> {
+        Ok(Self::from_data(&Data::parse(d)?))
+    }
+
+    /// Create a path from the `d` and `fill-rule` attributes of an SVG `path`
+    pub fn from_path(path: &Path) -> Self {
+        let attributes = path.get_attributes();
+        let bezier_path = attributes
+            .get("d")
+            .and_then(|d| Self::parse(d).ok())
+            .unwrap_or_default();
+
+        match fill_rule(attributes).as_deref() {
+            Some("evenodd") => bezier_path.with_fill_rule(FillRule::EvenOdd),
+            _ => bezier_path,
+        }
+    }
+
+    /// Load all drawn `path` elements in an SVG file as a single path
+    ///
+    /// The `transform` attributes of the paths and the groups around them are applied, and paths
+    /// that aren't drawn themselves, such as those in `defs`, `clipPath`, `mask` or `symbol`, are
+    /// skipped. Other styles are ignored. If any path has an `evenodd` fill rule, the whole path
+    /// is filled with it, so overlaps between the paths of such a file become holes.
+    pub fn from_svg_file(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        Self::from_svg(&std::fs::read_to_string(path)?)
+    }
+
+    /// Load all drawn `path` elements in SVG content as a single path
+    fn from_svg(content: &str) -> Result<Self, Error> {
+        let mut bezier_path = Self::default();
+        // What each open element passes on to its children, starting with the document
+        let mut stack = vec![Inherited {
+            transform: Affine::IDENTITY,
+            fill_rule: FillRule::NonZero,
+            hidden: false,
+        }];
+
+        for event in svg::read(content)? {
+            match event {
+                Event::Tag(name, kind @ (Type::Start | Type::Empty), attributes) => {
+                    let parent = *stack.last().expect("document is on the stack");
+                    let inherited = Inherited {
+                        transform: match attributes.get("transform") {
+                            Some(value) => parent.transform.then(Affine::parse(value)?),
+                            None => parent.transform,
+                        },
+                        fill_rule: match fill_rule(&attributes).as_deref() {
+                            Some("evenodd") => FillRule::EvenOdd,
+                            Some(_) => FillRule::NonZero,
+                            None => parent.fill_rule,
+                        },
+                        hidden: parent.hidden
+                            || matches!(name, "defs" | "clipPath" | "mask" | "symbol"),
+                    };
+
+                    if name == "path"
+                        && !inherited.hidden
+                        && let Some(d) = attributes.get("d")
+                    {
+                        let path = Self::parse(d)?.map(|point| inherited.transform.apply(point));
+                        bezier_path.segments.extend(path.segments);
+                        if inherited.fill_rule == FillRule::EvenOdd {
+                            bezier_path.fill_rule = FillRule::EvenOdd;
+                        }
+                    }
+                    if kind == Type::Start {
+                        stack.push(inherited);
+                    }
+                }
+                Event::Tag(_, Type::End, _) if stack.len() > 1 => {
+                    stack.pop();
+                }
+                Event::Error(error) => return Err(error.into()),
+                _ => {}
+            }
+        }
+
+        Ok(bezier_path)
+    }
+
+    /// Create a path from SVG path data
+    pub fn from_data(data: &Data) -> Self {
+        let mut normalizer = Normalizer::default();
+        for command in data.iter() {
+            normalizer.command(command);
+        }
+
+        Self::new(normalizer.segments)
+    }
+
+    /// Convert the path to SVG path data
+    pub fn to_data(&self) -> Data {
+        self.segments
+            .iter()
+            .fold(Data::new(), |data, segment| match segment {
+                Segment::MoveTo(point) => data.move_to(point.to_rounded_cartesian(3)),
+                Segment::LineTo(point) => data.line_to(point.to_rounded_cartesian(3)),
+                Segment::CubicTo(control1, control2, end) => {
+                    let (c1_x, c1_y) = control1.to_rounded_cartesian(3);
+                    let (c2_x, c2_y) = control2.to_rounded_cartesian(3);
+                    let (end_x, end_y) = end.to_rounded_cartesian(3);
+                    data.cubic_curve_to((c1_x, c1_y, c2_x, c2_y, end_x, end_y))
+                }
+                Segment::Close => data.close(),
+            })
+    }
+
+    /// Whether the path has no segments
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Apply `func` to every point of the path, including control points
+    ///
+    /// This is exact for affine transformations, such as translating, scaling, rotating and
+    /// skewing.
+    pub fn map(&self, func: impl Fn(Coordinate) -> Coordinate) -> Self {
+        Self {
+            segments: self
+                .segments
+                .iter()
+                .map(|segment| match *segment {
+                    Segment::MoveTo(point) => Segment::MoveTo(func(point)),
+                    Segment::LineTo(point) => Segment::LineTo(func(point)),
+                    Segment::CubicTo(control1, control2, end) => {
+                        Segment::CubicTo(func(control1), func(control2), func(end))
+                    }
+                    Segment::Close => Segment::Close,
+                })
+                .collect(),
+            fill_rule: self.fill_rule,
+        }
+    }
+
+    /// Rotate the path about the origin
+    pub fn rotate(&self, angle: Angle) -> Self {
+        self.map(|point| point.rotate(angle))
+    }
+
+    /// The tightest bounding box enclosing the path
+    ///
+    /// Unlike the control points, this only includes the extremes of each curve.
+    pub fn bounding_box(&self) -> BoundingBox {
+        let mut points = Vec::new();
+        let mut current = Coordinate::origin();
+
+        for segment in &self.segments {
+            match *segment {
+                Segment::MoveTo(point) | Segment::LineTo(point) => {
+                    points.push(point);
+                    current = point;
+                }
+                Segment::CubicTo(control1, control2, end) => {
+                    points.extend(cubic_extremes(current, control1, control2, end));
+                    points.push(end);
+                    current = end;
+                }
+                Segment::Close => {}
+            }
+        }
+
+        BoundingBox::from_points(&points)
+    }
+
+    /// Flatten the path into closed contours of straight lines
+    ///
+    /// Curves are split into segments roughly `segment_length` long, up to `max_segments` per
+    /// curve.
+    pub(crate) fn flatten(&self, segment_length: f64, max_segments: usize) -> Vec<Vec<Coordinate>> {
+        let mut contours = self.flatten_contours(segment_length, max_segments, false);
+        contours.retain(|contour| 3 <= contour.len());
+        contours
+    }
+
+    /// Flatten the path into lines, as they would be stroked
+    ///
+    /// Unlike [`BezierPath::flatten`], open lines are kept, and closed contours end back at their
+    /// start.
+    pub(crate) fn flatten_lines(
+        &self,
+        segment_length: f64,
+        max_segments: usize,
+    ) -> Vec<Vec<Coordinate>> {
+        let mut lines = self.flatten_contours(segment_length, max_segments, true);
+        lines.retain(|line| !line.is_empty());
+        lines
+    }
+
+    fn flatten_contours(
+        &self,
+        segment_length: f64,
+        max_segments: usize,
+        close_to_start: bool,
+    ) -> Vec<Vec<Coordinate>> {
+        let mut contours = Vec::new();
+        let mut contour: Vec<Coordinate> = Vec::new();
+
+        for segment in &self.segments {
+            match *segment {
+                Segment::MoveTo(point) => {
+                    contours.push(std::mem::take(&mut contour));
+                    contour.push(point);
+                }
+                Segment::LineTo(point) => contour.push(point),
+                Segment::CubicTo(control1, control2, end) => {
+                    let start = contour.last().copied().unwrap_or(control1);
+                    let length =
+                        start.dist(&control1) + control1.dist(&control2) + control2.dist(&end);
+                    let segments =
+                        ((length / segment_length).ceil() as usize).clamp(1, max_segments);
+
+                    contour.extend((1..=segments).map(|i| {
+                        cubic_point(start, control1, control2, end, i as f64 / segments as f64)
+                    }));
+                }
+                Segment::Close => {
+                    if close_to_start && let Some(start) = contour.first().copied() {
+                        contour.push(start);
+                    }
+                    contours.push(std::mem::take(&mut contour));
+                }
+            }
+        }
+        contours.push(contour);
+
+        contours
+    }
+}
+
+/// The fill rule of an element, from its attribute or its `style`
+fn fill_rule(attributes: &Attributes) -> Option<String> {
+    let from_style = attributes.get("style").and_then(|style| {
+        style.split(';').find_map(|declaration| {
+            let (property, value) = declaration.split_once(':')?;
+            (property.trim() == "fill-rule").then(|| value.trim().to_string())
+        })
+    });
+
+    from_style.or_else(|| {
+        attributes
+            .get("fill-rule")
+            .map(|value| value.trim().to_string())
+    })
+}
+
+/// What an SVG element passes on to the elements inside it
+#[derive(Debug, Clone, Copy)]
+struct Inherited {
+    transform: Affine,
+    fill_rule: FillRule,
+    /// Whether the element is only drawn where it's referenced, such as in `defs`
+    hidden: bool,
+}
+
+/// An affine transform from an SVG `transform` attribute, as the matrix `[a, b, c, d, e, f]`
+#[derive(Debug, Clone, Copy)]
+struct Affine([f64; 6]);
+
+impl Affine {
+    const IDENTITY: Self = Self([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    /// Parse a list of transforms, such as `translate(10 20) rotate(45)`
+    fn parse(value: &str) -> Result<Self, Error> {
+        let invalid = || {
+            Error::from(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid transform attribute",
+            ))
+        };
+        let mut transform = Self::IDENTITY;
+
+        let mut rest = value.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        while !rest.is_empty() {
+            let (function, after) = rest.split_once(')').ok_or_else(invalid)?;
+            let (name, arguments) = function.split_once('(').ok_or_else(invalid)?;
+            let arguments = arguments
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|argument| !argument.is_empty())
+                .map(|argument| argument.parse::<f64>().map_err(|_| invalid()))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let next = match (name.trim(), arguments.as_slice()) {
+                ("matrix", &[a, b, c, d, e, f]) => Self([a, b, c, d, e, f]),
+                ("translate", &[x]) => Self([1.0, 0.0, 0.0, 1.0, x, 0.0]),
+                ("translate", &[x, y]) => Self([1.0, 0.0, 0.0, 1.0, x, y]),
+                ("scale", &[x]) => Self([x, 0.0, 0.0, x, 0.0, 0.0]),
+                ("scale", &[x, y]) => Self([x, 0.0, 0.0, y, 0.0, 0.0]),
+                ("rotate", &[angle]) => Self::rotation(angle),
+                ("rotate", &[angle, x, y]) => Self([1.0, 0.0, 0.0, 1.0, x, y])
+                    .then(Self::rotation(angle))
+                    .then(Self([1.0, 0.0, 0.0, 1.0, -x, -y])),
+                ("skewX", &[angle]) => Self([1.0, 0.0, angle.to_radians().tan(), 1.0, 0.0, 0.0]),
+                ("skewY", &[angle]) => Self([1.0, angle.to_radians().tan(), 0.0, 1.0, 0.0, 0.0]),
+                _ => return Err(invalid()),
+            };
+
+            transform = transform.then(next);
+            rest = after.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        }
+
+        Ok(transform)
+    }
+
+    /// A rotation by `angle` degrees, clockwise on the screen
+    fn rotation(angle: f64) -> Self {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        Self([cos, sin, -sin, cos, 0.0, 0.0])
+    }
+
+    /// The transform applying `inner` first, then `self`, as SVG nests transforms
+    fn then(self, inner: Self) -> Self {
+        let [a1, b1, c1, d1, e1, f1] = self.0;
+        let [a2, b2, c2, d2, e2, f2] = inner.0;
+        Self([
+            a1 * a2 + c1 * b2,
+            b1 * a2 + d1 * b2,
+            a1 * c2 + c1 * d2,
+            b1 * c2 + d1 * d2,
+            a1 * e2 + c1 * f2 + e1,
+            b1 * e2 + d1 * f2 + f1,
+        ])
+    }
+
+    fn apply(self, point: Coordinate) -> Coordinate {
+        let [a, b, c, d, e, f] = self.0;
+        let (x, y) = point.to_cartesian();
+        Coordinate::Cartesian {
+            x: a * x + c * y + e,
+            y: b * x + d * y + f,
+        }
+    }
+}
+
+/// The point at `t` along a cubic Bézier curve
+fn cubic_point(
+    p0: Coordinate,
+    p1: Coordinate,
+    p2: Coordinate,
+    p3: Coordinate,
+    t: f64,
+) -> Coordinate {
+    let mt = 1.0 - t;
+    p0 * (mt * mt * mt) + p1 * (3.0 * mt * mt * t) + p2 * (3.0 * mt * t * t) + p3 * (t * t * t)
+}
+
+/// The points where a cubic Bézier curve turns back on itself in either axis
+fn cubic_extremes(
+    p0: Coordinate,
+    p1: Coordinate,
+    p2: Coordinate,
+    p3: Coordinate,
+) -> Vec<Coordinate> {
+    let (x0, y0) = p0.to_cartesian();
+    let (x1, y1) = p1.to_cartesian();
+    let (x2, y2) = p2.to_cartesian();
+    let (x3, y3) = p3.to_cartesian();
+
+    [(x0, x1, x2, x3), (y0, y1, y2, y3)]
+        .into_iter()
+        .flat_map(|(v0, v1, v2, v3)| {
+            // The derivative, divided by 3, is `a·t² + b·t + c`
+            let a = -v0 + 3.0 * v1 - 3.0 * v2 + v3;
+            let b = 2.0 * (v0 - 2.0 * v1 + v2);
+            let c = v1 - v0;
+            quadratic_roots(a, b, c)
+        })
+        .filter(|t| 0.0 < *t && *t < 1.0)
+        .map(|t| cubic_point(p0, p1, p2, p3, t))
+        .collect()
+}
+
+fn quadratic_roots(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < 1e-12 {
+        if b.abs() < 1e-12 {
+            Vec::new()
+        } else {
+            vec![-c / b]
+        }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            Vec::new()
+        } else {
+            let sqrt = discriminant.sqrt();
+            vec![(-b + sqrt) / (2.0 * a), (-b - sqrt) / (2.0 * a)]
+        }
+    }
+}
+
+/// Walks through path commands, converting them to absolute segments
+#[derive(Default)]
+struct Normalizer {
+    segments: Vec<Segment>,
+    current: Coordinate,
+    start: Coordinate,
+
+    /// Whether a new contour needs to be started before drawing, after the path is closed
+    closed: bool,
+
+    /// The last control point of the previous cubic curve, for smooth cubic curves
+    cubic_control: Option<Coordinate>,
+
+    /// The control point of the previous quadratic curve, for smooth quadratic curves
+    quadratic_control: Option<Coordinate>,
+}
+
+impl Normalizer {
+    fn command(&mut self, command: &Command) {
+        let (mut cubic_control, mut quadratic_control) = (None, None);
+
+        match command {
+            Command::Move(position, parameters) => {
+                for (i, chunk) in parameters.chunks_exact(2).enumerate() {
+                    let point = self.resolve(position, chunk[0], chunk[1]);
+                    if i == 0 {
+                        self.segments.push(Segment::MoveTo(point));
+                        self.start = point;
+                        self.current = point;
+                        self.closed = false;
+                    } else {
+                        self.line_to(point);
+                    }
+                }
+            }
+            Command::Line(position, parameters) => {
+                for chunk in parameters.chunks_exact(2) {
+                    let point = self.resolve(position, chunk[0], chunk[1]);
+                    self.line_to(point);
+                }
+            }
+            Command::HorizontalLine(position, parameters) => {
+                for &x in parameters.iter() {
+                    let (current_x, current_y) = self.current.to_cartesian();
+                    let x = match position {
+                        Position::Absolute => f64::from(x),
+                        Position::Relative => current_x + f64::from(x),
+                    };
+                    self.line_to(Coordinate::Cartesian { x, y: current_y });
+                }
+            }
+            Command::VerticalLine(position, parameters) => {
+                for &y in parameters.iter() {
+                    let (current_x, current_y) = self.current.to_cartesian();
+                    let y = match position {
+                        Position::Absolute => f64::from(y),
+                        Position::Relative => current_y + f64::from(y),
+                    };
+                    self.line_to(Coordinate::Cartesian { x: current_x, y });
+                }
+            }
+            Command::QuadraticCurve(position, parameters) => {
+                for chunk in parameters.chunks_exact(4) {
+                    let control = self.resolve(position, chunk[0], chunk[1]);
+                    let end = self.resolve(position, chunk[2], chunk[3]);
+                    self.quadratic_to(control, end);
+                    quadratic_control = Some(control);
+                }
+            }
+            Command::SmoothQuadraticCurve(position, parameters) => {
+                for chunk in parameters.chunks_exact(2) {
+                    let control = self.reflect(quadratic_control.or(self.quadratic_control));
+                    let end = self.resolve(position, chunk[0], chunk[1]);
+                    self.quadratic_to(control, end);
+                    quadratic_control = Some(control);
+                }
+            }
+            Command::CubicCurve(position, parameters) => {
+                for chunk in parameters.chunks_exact(6) {
+                    let control1 = self.resolve(position, chunk[0], chunk[1]);
+                    let control2 = self.resolve(position, chunk[2], chunk[3]);
+                    let end = self.resolve(position, chunk[4], chunk[5]);
+                    self.cubic_to(control1, control2, end);
+                    cubic_control = Some(control2);
+                }
+            }
+            Command::SmoothCubicCurve(position, parameters) => {
+                for chunk in parameters.chunks_exact(4) {
+                    let control1 = self.reflect(cubic_control.or(self.cubic_control));
+                    let control2 = self.resolve(position, chunk[0], chunk[1]);
+                    let end = self.resolve(position, chunk[2], chunk[3]);
+                    self.cubic_to(control1, control2, end);
+                    cubic_control = Some(control2);
+                }
+            }
+            Command::EllipticalArc(position, parameters) => {
+                for chunk in parameters.chunks_exact(7) {
+                    let end = self.resolve(position, chunk[5], chunk[6]);
+                    self.arc_to(
+                        (f64::from(chunk[0]), f64::from(chunk[1])),
+                        f64::from(chunk[2]),
+                        chunk[3] != 0.0,
+                        chunk[4] != 0.0,
+                        end,
+                    );
+                }
+            }
+            Command::Close => {
+                self.segments.push(Segment::Close);
+                self.current = self.start;
+                self.closed = true;
+            }
+        }
+
+        self.cubic_control = cubic_control;
+        self.quadratic_control = quadratic_control;
+    }
+
+    fn resolve(&self, position: &Position, x: f32, y: f32) -> Coordinate {
+        let point = Coordinate::Cartesian {
+            x: f64::from(x),
+            y: f64::from(y),
+        };
+        match position {
+            Position::Absolute => point,
+            Position::Relative => self.current + point,
+        }
+    }
+
+    /// Reflect the previous control point about the current point
+    fn reflect(&self, control: Option<Coordinate>) -> Coordinate {
+        match control {
+            Some(control) => self.current * 2.0 - control,
+            None => self.current,
+        }
+    }
+
+    /// Start a new contour if the previous one was closed without a move
+    fn ensure_contour(&mut self) {
+        if self.closed {
+            self.segments.push(Segment::MoveTo(self.current));
+            self.start = self.current;
+            self.closed = false;
+        }
+    }
+
+    fn line_to(&mut self, point: Coordinate) {
+        self.ensure_contour();
+        self.segments.push(Segment::LineTo(point));
+        self.current = point;
+    }
+
+    fn cubic_to(&mut self, control1: Coordinate, control2: Coordinate, end: Coordinate) {
+        self.ensure_contour();
+        self.segments
+            .push(Segment::CubicTo(control1, control2, end));
+        self.current = end;
+    }
+
+    fn quadratic_to(&mut self, control: Coordinate, end: Coordinate) {
+        let start = self.current;
+        self.cubic_to(
+            start.lerp(&control, 2.0 / 3.0),
+            end.lerp(&control, 2.0 / 3.0),
+            end,
+        );
+    }
+
+    /// Approximate an elliptical arc with cubic curves, converting from endpoint to center
+    /// parameterization
+    ///
+    /// See the [SVG implementation notes](https://www.w3.org/TR/SVG/implnote.html#ArcConversionEndpointToCenter).
+    fn arc_to(
+        &mut self,
+        radii: (f64, f64),
+        x_axis_rotation: f64,
+        large_arc: bool,
+        sweep: bool,
+        end: Coordinate,
+    ) {
+        let (start_x, start_y) = self.current.to_cartesian();
+        let (end_x, end_y) = end.to_cartesian();
+        let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+        if rx == 0.0 || ry == 0.0 || (start_x == end_x && start_y == end_y) {
+            self.line_to(end);
+            return;
+        }
+
+        let (sin_phi, cos_phi) = x_axis_rotation.to_radians().sin_cos();
+
+        let dx = (start_x - end_x) / 2.0;
+        let dy = (start_y - end_y) / 2.0;
+        let x1 = cos_phi * dx + sin_phi * dy;
+        let y1 = -sin_phi * dx + cos_phi * dy;
+
+        // Scale up the radii if they are too small to reach the end point
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if 1.0 < lambda {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+        let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+        let cx1 = coefficient * rx * y1 / ry;
+        let cy1 = -coefficient * ry * x1 / rx;
+
+        let cx = cos_phi * cx1 - sin_phi * cy1 + (start_x + end_x) / 2.0;
+        let cy = sin_phi * cx1 + cos_phi * cy1 + (start_y + end_y) / 2.0;
+
+        let theta1 = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+        let theta2 = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx);
+        let mut delta = theta2 - theta1;
+        if sweep && delta < 0.0 {
+            delta += 2.0 * PI;
+        } else if !sweep && 0.0 < delta {
+            delta -= 2.0 * PI;
+        }
+
+        // Map a point on the unit circle onto the ellipse
+        let to_ellipse = |x: f64, y: f64| Coordinate::Cartesian {
+            x: cx + rx * x * cos_phi - ry * y * sin_phi,
+            y: cy + rx * x * sin_phi + ry * y * cos_phi,
+        };
+
+        // Each curve spans at most a quarter turn, which keeps the approximation accurate
+        let curves = (delta.abs() / (PI / 2.0)).ceil().max(1.0) as usize;
+        let step = delta / curves as f64;
+        let k = 4.0 / 3.0 * (step / 4.0).tan();
+
+        for i in 0..curves {
+            let (sin_a, cos_a) = (theta1 + step * i as f64).sin_cos();
+            let (sin_b, cos_b) = (theta1 + step * (i + 1) as f64).sin_cos();
+
+            let control1 = to_ellipse(cos_a - k * sin_a, sin_a + k * cos_a);
+            let control2 = to_ellipse(cos_b + k * sin_b, sin_b - k * cos_b);
+            let point = if i + 1 == curves {
+                end
+            } else {
+                to_ellipse(cos_b, sin_b)
+            };
+
+            self.cubic_to(control1, control2, point);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(path: &BezierPath) -> Vec<(f64, f64)> {
+        path.segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::MoveTo(point) | Segment::LineTo(point) => {
+                    Some(point.to_rounded_cartesian(3))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn transforms_of_groups_and_paths_are_applied() {
+        let path = BezierPath::from_svg(
+            r#"<svg xmlns="http://www.w3.org/2000/svg">
+                <g transform="translate(10, 0)">
+                    <path d="M 1 0 L 2 0" transform="scale(2) rotate(90)"/>
+                </g>
+                <path d="M 1 1"/>
+            </svg>"#,
+        )
+        .unwrap();
+
+        assert_eq!(points(&path), vec![(10.0, 2.0), (10.0, 4.0), (1.0, 1.0)]);
+    }
+
+    #[test]
+    fn rotating_about_a_point_keeps_it_in_place() {
+        let transform = Affine::parse("rotate(90 5 5)").unwrap();
+        let (x, y) = transform
+            .apply(Coordinate::Cartesian { x: 5.0, y: 5.0 })
+            .to_rounded_cartesian(3);
+
+        assert_eq!((x, y), (5.0, 5.0));
+    }
+
+    #[test]
+    fn invalid_transforms_are_rejected() {
+        let svg = r#"<svg><path d="M 0 0 L 1 1" transform="perspective(2)"/></svg>"#;
+
+        assert!(BezierPath::from_svg(svg).is_err());
+    }
+
+    #[test]
+    fn fill_rules_are_inherited() {
+        let fill_rule = |svg| BezierPath::from_svg(svg).unwrap().fill_rule;
+
+        assert_eq!(
+            fill_rule(r#"<svg><path d="M 0 0 L 1 1" fill-rule="evenodd"/></svg>"#),
+            FillRule::EvenOdd
+        );
+        assert_eq!(
+            fill_rule(r#"<svg><g style="fill-rule: evenodd"><path d="M 0 0 L 1 1"/></g></svg>"#),
+            FillRule::EvenOdd
+        );
+        assert_eq!(
+            fill_rule(
+                r#"<svg fill-rule="evenodd"><path d="M 0 0 L 1 1" fill-rule="nonzero"/></svg>"#
+            ),
+            FillRule::NonZero
+        );
+    }
+
+    #[test]
+    fn paths_that_are_not_drawn_are_skipped() {
+        let path = BezierPath::from_svg(
+            r#"<svg xmlns="http://www.w3.org/2000/svg">
+                <defs><path d="M 5 5" fill-rule="evenodd"/></defs>
+                <clipPath><path d="M 6 6"/></clipPath>
+                <mask><g><path d="M 7 7"/></g></mask>
+                <symbol><path d="M 8 8"/></symbol>
+                <path d="M 1 1"/>
+            </svg>"#,
+        )
+        .unwrap();
+
+        assert_eq!(points(&path), vec![(1.0, 1.0)]);
+        assert_eq!(path.fill_rule, FillRule::NonZero);
+    }
+}
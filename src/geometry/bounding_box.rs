@@ -44,6 +44,41 @@ impl BoundingBox {
         )
     }
 
+    /// Create an empty bounding box, which encloses nothing and intersects nothing
+    pub fn empty() -> Self {
+        Self::from_points([])
+    }
+
+    /// Test whether the bounding box encloses nothing
+    pub fn is_empty(&self) -> bool {
+        let (min_x, min_y) = self.0.to_cartesian();
+        let (max_x, max_y) = self.1.to_cartesian();
+
+        !(min_x <= max_x && min_y <= max_y)
+    }
+
+    /// Create a new bounding box enclosing the corners of this one after applying `func`
+    ///
+    /// This is conservative for rotations and exact for translations and scaling.
+    pub fn map(&self, func: impl Fn(Coordinate) -> Coordinate) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+
+        let (min_x, min_y) = self.0.to_cartesian();
+        let (max_x, max_y) = self.1.to_cartesian();
+
+        Self::from_points(
+            &[
+                (min_x, min_y),
+                (max_x, min_y),
+                (max_x, max_y),
+                (min_x, max_y),
+            ]
+            .map(|corner| func(corner.into())),
+        )
+    }
+
     /// Return the coordinate for minimum bound
     pub fn min(&self) -> Coordinate {
         self.0
@@ -93,7 +128,15 @@ impl BoundingBox {
 impl Add for BoundingBox {
     type Output = BoundingBox;
 
+    /// The bounding box enclosing both boxes, where an empty box adds nothing
     fn add(self, rhs: BoundingBox) -> Self::Output {
+        if self.is_empty() {
+            return rhs;
+        }
+        if rhs.is_empty() {
+            return self;
+        }
+
         let BoundingBox(self_min, self_max) = self;
         let BoundingBox(other_min, other_max) = rhs;
 
@@ -111,7 +154,7 @@ impl Add for BoundingBox {
             y: self_max_y.max(other_max_y),
         };
 
-        BoundingBox::new(coordinate1, coordinate2)
+        BoundingBox(coordinate1, coordinate2)
     }
 }
 
@@ -130,3 +173,54 @@ impl Sub<Coordinate> for BoundingBox {
         BoundingBox(self.0 - rhs, self.1 - rhs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bb(min: (f64, f64), max: (f64, f64)) -> BoundingBox {
+        BoundingBox::new(min.into(), max.into())
+    }
+
+    fn corners(bb: BoundingBox) -> ((f64, f64), (f64, f64)) {
+        (bb.min().to_cartesian(), bb.max().to_cartesian())
+    }
+
+    #[test]
+    fn empty_encloses_nothing() {
+        let empty = BoundingBox::empty();
+
+        assert!(empty.is_empty());
+        assert!(!empty.intersects(&bb((-1e9, -1e9), (1e9, 1e9))));
+        assert!(!bb((0.0, 0.0), (1.0, 1.0)).is_empty());
+    }
+
+    #[test]
+    fn add_encloses_both_boxes() {
+        let sum = bb((0.0, 0.0), (1.0, 1.0)) + bb((2.0, -1.0), (3.0, 0.5));
+
+        assert_eq!(corners(sum), ((0.0, -1.0), (3.0, 1.0)));
+    }
+
+    #[test]
+    fn add_ignores_empty_boxes() {
+        let a = bb((1.0, 2.0), (3.0, 4.0));
+
+        assert_eq!(corners(a + BoundingBox::empty()), corners(a));
+        assert_eq!(corners(BoundingBox::empty() + a), corners(a));
+        assert!((BoundingBox::empty() + BoundingBox::empty()).is_empty());
+    }
+
+    #[test]
+    fn map_encloses_transformed_corners() {
+        let a = bb((0.0, 0.0), (2.0, 1.0));
+
+        let scaled = a.map(|c| c * -2.0);
+        assert_eq!(corners(scaled), ((-4.0, -2.0), (0.0, 0.0)));
+
+        let moved = a.map(|c| c + Coordinate::Cartesian { x: 1.0, y: 1.0 });
+        assert_eq!(corners(moved), ((1.0, 1.0), (3.0, 2.0)));
+
+        assert!(BoundingBox::empty().map(|c| c * 2.0).is_empty());
+    }
+}
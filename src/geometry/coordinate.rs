@@ -1,4 +1,4 @@
-use std::ops::{Add, Div, Sub};
+use std::ops::{Add, Div, Mul, Sub};
 
 use crate::geometry::Angle;
 
@@ -33,6 +33,15 @@ impl Coordinate {
         }
     }
 
+    /// Rotate the coordinate about the origin
+    pub fn rotate(self, angle: Angle) -> Self {
+        let (r, phi) = self.to_polar();
+        Coordinate::Polar {
+            r,
+            phi: phi + angle,
+        }
+    }
+
     pub(crate) fn to_rounded_cartesian(self, decimals: i32) -> (f64, f64) {
         let mult = 10_f64.powi(decimals);
         let (x, y) = self.to_cartesian();
@@ -92,6 +101,25 @@ impl Div<f64> for Coordinate {
     }
 }
 
+impl From<(f64, f64)> for Coordinate {
+    fn from((x, y): (f64, f64)) -> Self {
+        Coordinate::Cartesian { x, y }
+    }
+}
+
+impl Mul<f64> for Coordinate {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        let (self_x, self_y) = self.to_cartesian();
+
+        Self::Cartesian {
+            x: self_x * rhs,
+            y: self_y * rhs,
+        }
+    }
+}
+
 impl Sub for Coordinate {
     type Output = Self;
 
@@ -0,0 +1,104 @@
+use svg::node::element::path::Data;
+
+use crate::geometry::Coordinate;
+
+/// The number of evenly spaced samples taken before refining a curve
+const INITIAL_SAMPLES: usize = 64;
+
+/// The maximum number of times a span of the curve is halved
+const MAX_DEPTH: u8 = 8;
+
+/// Sample the closed curve `curve` for `t` in `start..end`
+///
+/// Spans of the curve are halved until their midpoint lies within `tolerance` of the straight line
+/// between their ends. The point at `end` is not included, as it is the same as the point at
+/// `start`.
+pub(crate) fn sample_closed_curve(
+    curve: impl Fn(f64) -> Coordinate,
+    start: f64,
+    end: f64,
+    tolerance: f64,
+) -> Vec<Coordinate> {
+    let step = (end - start) / INITIAL_SAMPLES as f64;
+    let mut points = Vec::with_capacity(INITIAL_SAMPLES);
+
+    for i in 0..INITIAL_SAMPLES {
+        let t0 = start + step * i as f64;
+        let t1 = t0 + step;
+        let p0 = curve(t0);
+
+        points.push(p0);
+        refine(
+            &curve,
+            (t0, p0),
+            (t1, curve(t1)),
+            tolerance,
+            MAX_DEPTH,
+            &mut points,
+        );
+    }
+
+    points
+}
+
+fn refine(
+    curve: &impl Fn(f64) -> Coordinate,
+    (t0, p0): (f64, Coordinate),
+    (t1, p1): (f64, Coordinate),
+    tolerance: f64,
+    depth: u8,
+    points: &mut Vec<Coordinate>,
+) {
+    let t_mid = (t0 + t1) / 2.0;
+    let p_mid = curve(t_mid);
+
+    if depth == 0 || p_mid.dist(&p0.lerp(&p1, 0.5)) <= tolerance {
+        return;
+    }
+
+    refine(
+        curve,
+        (t0, p0),
+        (t_mid, p_mid),
+        tolerance,
+        depth - 1,
+        points,
+    );
+    points.push(p_mid);
+    refine(
+        curve,
+        (t_mid, p_mid),
+        (t1, p1),
+        tolerance,
+        depth - 1,
+        points,
+    );
+}
+
+/// Draw a smooth closed path through `points`
+///
+/// Each span between points is a cubic Bézier curve, with control points set so that the path
+/// forms a Catmull-Rom spline.
+pub(crate) fn smooth_closed_data(points: &[Coordinate]) -> Data {
+    let Some(first) = points.first() else {
+        return Data::new();
+    };
+
+    let n = points.len();
+    let mut data = Data::new().move_to(first.to_rounded_cartesian(3));
+
+    for i in 0..n {
+        let p0 = points[(i + n - 1) % n];
+        let p1 = points[i];
+        let p2 = points[(i + 1) % n];
+        let p3 = points[(i + 2) % n];
+
+        let (c1_x, c1_y) = (p1 + (p2 - p0) / 6.0).to_rounded_cartesian(3);
+        let (c2_x, c2_y) = (p2 - (p3 - p1) / 6.0).to_rounded_cartesian(3);
+        let (end_x, end_y) = p2.to_rounded_cartesian(3);
+
+        data = data.cubic_curve_to((c1_x, c1_y, c2_x, c2_y, end_x, end_y));
+    }
+
+    data.close()
+}
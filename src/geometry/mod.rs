@@ -1,7 +1,12 @@
 mod angle;
+mod bezier_path;
 mod bounding_box;
 mod coordinate;
+pub(crate) mod curve;
+mod outline;
 
 pub use angle::Angle;
+pub use bezier_path::{BezierPath, FillRule, Segment};
 pub use bounding_box::BoundingBox;
 pub use coordinate::Coordinate;
+pub use outline::Outline;
@@ -0,0 +1,125 @@
+use i_overlay::{
+    core::{fill_rule::FillRule, overlay_rule::OverlayRule},
+    float::single::SingleFloatOverlay,
+};
+use svg::node::element::{Path, path::Data};
+
+use crate::geometry::{BezierPath, BoundingBox, Coordinate};
+
+/// The approximate length of the line segments used to flatten curves
+const SEGMENT_LENGTH: f64 = 2.0;
+
+/// The maximum number of line segments used to flatten a single curve
+const MAX_SEGMENTS: usize = 64;
+
+/// The outline of a shape, made up of closed contours of straight line segments
+///
+/// Curves are approximated by line segments. The area enclosed by the outline follows the
+/// even-odd rule, so a contour inside of another contour is a hole.
+#[derive(Debug, Clone, Default)]
+pub struct Outline {
+    /// The closed contours of the outline
+    pub contours: Vec<Vec<Coordinate>>,
+}
+
+impl Outline {
+    /// Create a new outline
+    pub fn new(contours: Vec<Vec<Coordinate>>) -> Self {
+        Self { contours }
+    }
+
+    /// Create an outline from the `d` attribute of an SVG `path`
+    pub fn from_path(path: &Path) -> Self {
+        Self::from_bezier_path(&BezierPath::from_path(path))
+    }
+
+    /// Create an outline from SVG path data, flattening any curves
+    pub fn from_data(data: &Data) -> Self {
+        Self::from_bezier_path(&BezierPath::from_data(data))
+    }
+
+    /// Create an outline from a [`BezierPath`], flattening any curves
+    pub fn from_bezier_path(path: &BezierPath) -> Self {
+        Self::new(path.flatten(SEGMENT_LENGTH, MAX_SEGMENTS))
+    }
+
+    /// Convert the outline to SVG path data
+    pub fn to_data(&self) -> Data {
+        self.contours
+            .iter()
+            .filter_map(|contour| contour.split_first())
+            .fold(Data::new(), |data, (first, rest)| {
+                rest.iter()
+                    .fold(
+                        data.move_to(first.to_rounded_cartesian(3)),
+                        |data, point| data.line_to(point.to_rounded_cartesian(3)),
+                    )
+                    .close()
+            })
+    }
+
+    /// Whether the outline has no contours
+    pub fn is_empty(&self) -> bool {
+        self.contours.is_empty()
+    }
+
+    /// The bounding box enclosing all contours
+    pub fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::from_points(self.contours.iter().flatten())
+    }
+
+    /// The area covered by either outline
+    pub fn union(&self, other: &Self) -> Self {
+        self.overlay(other, OverlayRule::Union)
+    }
+
+    /// The area covered by this outline but not the `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        self.overlay(other, OverlayRule::Difference)
+    }
+
+    /// The area covered by both outlines
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.overlay(other, OverlayRule::Intersect)
+    }
+
+    /// The area covered by exactly one of the outlines
+    pub fn xor(&self, other: &Self) -> Self {
+        self.overlay(other, OverlayRule::Xor)
+    }
+
+    fn overlay(&self, other: &Self, rule: OverlayRule) -> Self {
+        let subject = self.to_points();
+        let clip = other.to_points();
+
+        let shapes = subject.overlay(&clip, rule, FillRule::EvenOdd);
+
+        Self::new(
+            shapes
+                .into_iter()
+                .flatten()
+                .map(|contour| {
+                    contour
+                        .into_iter()
+                        .map(|[x, y]| Coordinate::Cartesian { x, y })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    fn to_points(&self) -> Vec<Vec<[f64; 2]>> {
+        self.contours
+            .iter()
+            .map(|contour| {
+                contour
+                    .iter()
+                    .map(|point| {
+                        let (x, y) = point.to_cartesian();
+                        [x, y]
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,391 @@
+//! Where the points of a [`PointSet`](crate::point_set::PointSet) are placed on the
+//! [`Canvas`](crate::Canvas)
+use crate::geometry::{Angle, BoundingBox, Coordinate};
+
+/// How the points are scaled to the area of the canvas inside the margins
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Fit {
+    /// Keep the points at their own scale
+    #[default]
+    None,
+
+    /// Scale the points to fit inside the area, leaving space along one side
+    Contain,
+
+    /// Scale the points to cover the whole area, cutting off points along one side
+    Cover,
+}
+
+/// Where the points are placed within the area of the canvas inside the margins
+///
+/// This decides which side any leftover space is on, or which side is cut off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// How far along the leftover space the points are placed, from `0.0` to `1.0` in each axis
+    fn fraction(self) -> (f64, f64) {
+        match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::Top => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::Bottom => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        }
+    }
+}
+
+/// The space left empty around the edges of the canvas
+///
+/// Shapes can still be drawn in the margin around points near the edges.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Margin {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+impl Margin {
+    /// The same margin on every side
+    pub fn uniform(margin: f64) -> Self {
+        Self::symmetric(margin, margin)
+    }
+
+    /// One margin on the top and bottom, and another on the left and right
+    pub fn symmetric(vertical: f64, horizontal: f64) -> Self {
+        Self {
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+            left: horizontal,
+        }
+    }
+}
+
+impl From<f64> for Margin {
+    fn from(margin: f64) -> Self {
+        Self::uniform(margin)
+    }
+}
+
+/// A transform of the whole layout of points, such as to lay a lattice out diagonally
+///
+/// Transforms are combined in the order they are added, and are applied around the center of the
+/// points. Only the positions of the points are changed, so shapes keep their own orientation.
+///
+/// ```rust,ignore
+/// let transform = Transform::new().rotate(Angle::Degree(45.0)).scale(1.0, 0.5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    /// The matrix of the transform, in the order of the SVG `matrix(a b c d 0 0)` function
+    matrix: [f64; 4],
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transform {
+    /// The identity transform, which leaves the points where they are
+    pub fn new() -> Self {
+        Self {
+            matrix: [1.0, 0.0, 0.0, 1.0],
+        }
+    }
+
+    /// Whether the transform leaves the points where they are
+    pub fn is_identity(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Rotate the points clockwise by `angle`
+    pub fn rotate(self, angle: Angle) -> Self {
+        let (sin, cos) = angle.to_radian().sin_cos();
+        self.then([cos, sin, -sin, cos])
+    }
+
+    /// Scale the points by `x` horizontally and `y` vertically
+    pub fn scale(self, x: f64, y: f64) -> Self {
+        self.then([x, 0.0, 0.0, y])
+    }
+
+    /// Skew the points by `x` along the x-axis and `y` along the y-axis, as in SVG
+    pub fn skew(self, x: Angle, y: Angle) -> Self {
+        self.then([1.0, y.to_radian().tan(), x.to_radian().tan(), 1.0])
+    }
+
+    /// Mirror the points from left to right
+    pub fn mirror_x(self) -> Self {
+        self.scale(-1.0, 1.0)
+    }
+
+    /// Mirror the points from top to bottom
+    pub fn mirror_y(self) -> Self {
+        self.scale(1.0, -1.0)
+    }
+
+    /// Apply `matrix` after the transform
+    fn then(self, [a2, b2, c2, d2]: [f64; 4]) -> Self {
+        let [a1, b1, c1, d1] = self.matrix;
+        Self {
+            matrix: [
+                a2 * a1 + c2 * b1,
+                b2 * a1 + d2 * b1,
+                a2 * c1 + c2 * d1,
+                b2 * c1 + d2 * d1,
+            ],
+        }
+    }
+
+    /// Transform a coordinate around the origin
+    pub fn apply(&self, coordinate: Coordinate) -> Coordinate {
+        let [a, b, c, d] = self.matrix;
+        let (x, y) = coordinate.to_cartesian();
+        Coordinate::Cartesian {
+            x: a * x + c * y,
+            y: b * x + d * y,
+        }
+    }
+
+    /// The smallest factor that any vector is scaled by
+    pub(crate) fn min_scale(&self) -> f64 {
+        // The smallest singular value of the matrix
+        let [a, b, c, d] = self.matrix;
+        let sum = a * a + b * b + c * c + d * d;
+        let determinant = a * d - b * c;
+        let root = (sum * sum - 4.0 * determinant * determinant)
+            .max(0.0)
+            .sqrt();
+
+        ((sum - root) / 2.0).max(0.0).sqrt()
+    }
+
+    /// The transform as an SVG `matrix`, with no translation
+    pub(crate) fn to_svg(self) -> String {
+        let [a, b, c, d] = self.matrix.map(|value| (value * 1e6).round() / 1e6 + 0.0);
+        format!("matrix({a},{b},{c},{d},0,0)")
+    }
+
+    /// The size of the area that covers `width` by `height` once it is transformed
+    ///
+    /// A point set covering this area, centered on the canvas, still covers a canvas of `width`
+    /// by `height` after the transform. Transforms that flatten the points onto a line can't
+    /// cover an area, so the size is left as it is.
+    pub fn cover(&self, width: f64, height: f64) -> (f64, f64) {
+        let [a, b, c, d] = self.matrix;
+        let determinant = a * d - b * c;
+        if determinant.abs() < 1e-12 {
+            return (width, height);
+        }
+
+        let inverse = Self {
+            matrix: [
+                d / determinant,
+                -b / determinant,
+                -c / determinant,
+                a / determinant,
+            ],
+        };
+        let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].map(|(x, y)| {
+            inverse.apply(Coordinate::Cartesian {
+                x: x * width / 2.0,
+                y: y * height / 2.0,
+            })
+        });
+
+        BoundingBox::from_points(&corners).span().to_cartesian()
+    }
+}
+
+/// The mapping from the coordinates of a point set to the canvas
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Layout {
+    /// How much the point set is scaled
+    pub scale: f64,
+
+    /// The transform of the point set
+    transform: Transform,
+
+    /// The corner of the bounding box of the transformed point set
+    origin: Coordinate,
+
+    /// Where the corner of the bounding box is placed on the canvas
+    offset: Coordinate,
+}
+
+impl Layout {
+    /// Lay out the points inside `bb` on a canvas of `size`
+    ///
+    /// The points are fit and anchored by the bounding box they have once transformed.
+    pub(crate) fn new(
+        bb: BoundingBox,
+        size: (f64, f64),
+        fit: Fit,
+        margin: Margin,
+        anchor: Anchor,
+        transform: Transform,
+    ) -> Self {
+        let scale = Self::fit_scale(bb, size, fit, margin, transform);
+        Self::with_scale(bb, size, scale, margin, anchor, transform)
+    }
+
+    /// How much the points inside `bb` are scaled to `fit` a canvas of `size`
+    pub(crate) fn fit_scale(
+        bb: BoundingBox,
+        size: (f64, f64),
+        fit: Fit,
+        margin: Margin,
+        transform: Transform,
+    ) -> f64 {
+        let (area_x, area_y) = area(size, margin);
+        let (span_x, span_y) = bb.map(|point| transform.apply(point)).span().to_cartesian();
+
+        // A point set with no width or height is scaled by its other side
+        let scales = [area_x / span_x, area_y / span_y]
+            .into_iter()
+            .filter(|scale| scale.is_finite() && 0.0 < *scale);
+        match fit {
+            Fit::None => None,
+            Fit::Contain => scales.reduce(f64::min),
+            Fit::Cover => scales.reduce(f64::max),
+        }
+        .unwrap_or(1.0)
+    }
+
+    /// Lay out the points inside `bb` on a canvas of `size` at a given `scale`
+    pub(crate) fn with_scale(
+        bb: BoundingBox,
+        size: (f64, f64),
+        scale: f64,
+        margin: Margin,
+        anchor: Anchor,
+        transform: Transform,
+    ) -> Self {
+        let bb = bb.map(|point| transform.apply(point));
+
+        let area = area(size, margin);
+        let (span_x, span_y) = bb.span().to_cartesian();
+
+        let (fraction_x, fraction_y) = anchor.fraction();
+        let offset = Coordinate::Cartesian {
+            x: margin.left + (area.0 - span_x * scale) * fraction_x,
+            y: margin.top + (area.1 - span_y * scale) * fraction_y,
+        };
+
+        Self {
+            scale,
+            transform,
+            origin: bb.min(),
+            offset,
+        }
+    }
+
+    /// The transform of vectors between points of the point set, for shapes drawn at
+    /// `shape_scale`
+    pub(crate) fn vector_transform(&self, shape_scale: f64) -> Transform {
+        let scale = self.scale / shape_scale;
+        self.transform.scale(scale, scale)
+    }
+
+    /// Map a coordinate of the point set to the canvas
+    pub(crate) fn place(&self, coordinate: Coordinate) -> Coordinate {
+        self.offset + (self.transform.apply(coordinate) - self.origin) * self.scale
+    }
+}
+
+/// The area of a canvas of `size` inside the `margin`
+fn area(size: (f64, f64), margin: Margin) -> (f64, f64) {
+    (
+        size.0 - margin.left - margin.right,
+        size.1 - margin.top - margin.bottom,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f64, y: f64) -> Coordinate {
+        Coordinate::Cartesian { x, y }
+    }
+
+    fn assert_near(a: Coordinate, b: Coordinate) {
+        assert!(a.dist(&b) < 1e-9, "{a:?} is not {b:?}");
+    }
+
+    #[test]
+    fn transforms_apply_in_the_order_they_are_added() {
+        let rotate_then_scale = Transform::new().rotate(Angle::Degree(90.0)).scale(2.0, 1.0);
+        let scale_then_rotate = Transform::new().scale(2.0, 1.0).rotate(Angle::Degree(90.0));
+
+        assert_near(rotate_then_scale.apply(point(1.0, 0.0)), point(0.0, 1.0));
+        assert_near(scale_then_rotate.apply(point(1.0, 0.0)), point(0.0, 2.0));
+    }
+
+    #[test]
+    fn mirrors_undo_themselves() {
+        let transform = Transform::new().mirror_x().mirror_x().mirror_y().mirror_y();
+
+        assert!(transform.is_identity());
+        assert_near(
+            Transform::new().mirror_x().apply(point(3.0, 4.0)),
+            point(-3.0, 4.0),
+        );
+    }
+
+    #[test]
+    fn min_scale_is_the_least_stretch() {
+        let transform = Transform::new().scale(3.0, 0.5).rotate(Angle::Degree(30.0));
+        assert!((transform.min_scale() - 0.5).abs() < 1e-9);
+        assert!((Transform::new().min_scale() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cover_undoes_the_transform() {
+        let transform = Transform::new().scale(2.0, 0.5);
+
+        let (width, height) = transform.cover(100.0, 100.0);
+        assert!((width - 50.0).abs() < 1e-9 && (height - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn layout_keeps_a_given_scale() {
+        let bb = BoundingBox::new(point(0.0, 0.0), point(10.0, 20.0));
+        let margin = Margin::default();
+
+        let scale = Layout::fit_scale(bb, (100.0, 100.0), Fit::Contain, margin, Transform::new());
+        assert_eq!(scale, 5.0);
+
+        // A larger set at the same scale overflows the canvas evenly on both sides
+        let larger = BoundingBox::new(point(0.0, 0.0), point(40.0, 20.0));
+        let layout = Layout::with_scale(
+            larger,
+            (100.0, 100.0),
+            scale,
+            margin,
+            Anchor::Center,
+            Transform::new(),
+        );
+        assert_near(layout.place(point(0.0, 0.0)), point(-50.0, 0.0));
+        assert_near(layout.place(point(40.0, 20.0)), point(150.0, 100.0));
+    }
+}
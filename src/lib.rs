@@ -7,8 +7,9 @@
 //! to be drawn on each point in the `PointSet`.
 //!
 //! Each point in the `PointSet` has an associated [`Index`](point_set::PointSet::Index). This gets
-//! passed to the `Shape` so that you can control if and how the shape gets rendered depending on
-//! its `Index`.
+//! passed to the `Shape` along with the point's position on the canvas as a
+//! [`Context`](shape::Context), so that you can control if and how the shape gets rendered
+//! depending on its `Index` or where it is on the canvas.
 //!
 //! # Example
 //!
@@ -16,11 +17,23 @@
 #![doc = include_str!("../examples/kanoko.rs")]
 //! ```
 
+pub mod background;
 pub mod canvas;
 mod color;
+pub mod effect;
+mod error;
 pub mod geometry;
+pub mod layout;
+pub mod noise;
+pub mod order;
+pub mod overlap;
 pub mod point_set;
+pub mod print;
 pub mod shape;
+mod stream;
+pub mod symmetry;
+pub mod tile;
 
-pub use canvas::Canvas;
+pub use canvas::{Canvas, Grouping, Layer};
 pub use color::Color;
+pub use error::Error;
@@ -0,0 +1,71 @@
+//! Helpers for using noise to vary shapes across the canvas
+use crate::{Color, geometry::Angle, noise::Noise, shape::Context};
+
+/// A [`Noise`] laid over the canvas
+///
+/// The methods turn the field into functions of the [`Context`], which can be passed to the
+/// `_context_fn` setters of shapes such as [`Polygon`](crate::shape::Polygon).
+///
+/// ```rust,ignore
+/// let field = NoiseField::new(Fbm::builder().source(Simplex::new(42)).build(), 0.002);
+/// let polygon = Polygon::builder()
+///     .sides(6)
+///     .size_context_fn(field.clone().size_fn(40.0, 80.0))
+///     .color_context_fn(field.color_fn(vec![indigo, white]))
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct NoiseField<N> {
+    /// The noise sampled for each point
+    pub noise: N,
+
+    /// The scale from canvas coordinates to noise coordinates
+    ///
+    /// Smaller values give broader, smoother variation.
+    pub frequency: f64,
+}
+
+impl<N: Noise + 'static> NoiseField<N> {
+    /// Define a new noise field
+    pub fn new(noise: N, frequency: f64) -> Self {
+        Self { noise, frequency }
+    }
+
+    /// Sample the field at the point in `context`, normalized to `0.0..=1.0`
+    pub fn sample<I>(&self, context: &Context<I>) -> f64 {
+        ((self.noise.get(context.coordinate * self.frequency) + 1.0) / 2.0).clamp(0.0, 1.0)
+    }
+
+    /// A function mapping the sampled value (`0.0..=1.0`) with `func`
+    pub fn map_fn<I, T>(self, func: impl Fn(f64) -> T + 'static) -> impl Fn(&Context<I>) -> T {
+        move |context| func(self.sample(context))
+    }
+
+    /// A size function that varies between `min` and `max`
+    pub fn size_fn<I>(self, min: f64, max: f64) -> impl Fn(&Context<I>) -> f64 {
+        self.map_fn(move |t| min + t * (max - min))
+    }
+
+    /// A rotation function that varies between `-max` and `max`
+    pub fn rotation_fn<I>(self, max: Angle) -> impl Fn(&Context<I>) -> Angle {
+        self.map_fn(move |t| Angle::Radian((t * 2.0 - 1.0) * max.to_radian()))
+    }
+
+    /// A color function that blends through `colors`, evenly spaced along the range of the noise
+    pub fn color_fn<I>(self, colors: Vec<Color>) -> impl Fn(&Context<I>) -> Color {
+        self.map_fn(move |t| match colors.len() {
+            0 => Color::new(0, 0, 0, 0),
+            1 => colors[0],
+            len => {
+                let position = t * (len - 1) as f64;
+                let i = (position.floor() as usize).min(len - 2);
+                colors[i].lerp(&colors[i + 1], position - i as f64)
+            }
+        })
+    }
+
+    /// A coefficient of variance function that varies between `min` and `max`
+    pub fn cv_fn<I>(self, min: f64, max: f64) -> impl Fn(&Context<I>) -> f64 {
+        self.map_fn(move |t| min + t * (max - min))
+    }
+}
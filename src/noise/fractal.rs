@@ -0,0 +1,92 @@
+//! Fractal noise, made by layering octaves of a source noise
+use crate::{geometry::Coordinate, noise::Noise};
+
+/// Fractal Brownian motion
+///
+/// Sums `octaves` layers of the `source` noise, each at a higher frequency and lower amplitude
+/// than the last. This adds finer detail to the broad variation of the source.
+#[derive(Debug, Clone, bon::Builder)]
+pub struct Fbm<N> {
+    /// The noise that is layered
+    pub source: N,
+
+    /// The number of layers
+    #[builder(default = 4)]
+    pub octaves: u8,
+
+    /// The factor the frequency is multiplied by for each octave
+    #[builder(default = 2.0)]
+    pub lacunarity: f64,
+
+    /// The factor the amplitude is multiplied by for each octave
+    #[builder(default = 0.5)]
+    pub gain: f64,
+}
+
+impl<N: Noise> Noise for Fbm<N> {
+    fn get(&self, point: Coordinate) -> f64 {
+        let (mut sum, mut amplitude_sum) = (0.0, 0.0);
+        let (mut frequency, mut amplitude) = (1.0, 1.0);
+
+        for _ in 0..self.octaves {
+            sum += amplitude * self.source.get(point * frequency);
+            amplitude_sum += amplitude;
+
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+
+        if amplitude_sum > 0.0 {
+            sum / amplitude_sum
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Ridged multifractal noise
+///
+/// Like [`Fbm`], but each octave is folded at zero so that the result has sharp ridges, like
+/// the creases left in a piece of folded cloth.
+#[derive(Debug, Clone, bon::Builder)]
+pub struct Ridged<N> {
+    /// The noise that is layered
+    pub source: N,
+
+    /// The number of layers
+    #[builder(default = 4)]
+    pub octaves: u8,
+
+    /// The factor the frequency is multiplied by for each octave
+    #[builder(default = 2.0)]
+    pub lacunarity: f64,
+
+    /// The factor the amplitude is multiplied by for each octave
+    #[builder(default = 0.5)]
+    pub gain: f64,
+}
+
+impl<N: Noise> Noise for Ridged<N> {
+    fn get(&self, point: Coordinate) -> f64 {
+        let (mut sum, mut amplitude_sum) = (0.0, 0.0);
+        let (mut frequency, mut amplitude) = (1.0, 1.0);
+        let mut weight = 1.0;
+
+        for _ in 0..self.octaves {
+            let signal = (1.0 - self.source.get(point * frequency).abs()).powi(2) * weight;
+            weight = signal.clamp(0.0, 1.0);
+
+            sum += amplitude * signal;
+            amplitude_sum += amplitude;
+
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+
+        if amplitude_sum > 0.0 {
+            sum / amplitude_sum * 2.0 - 1.0
+        } else {
+            0.0
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Noise fields for spatially coherent variation
+//!
+//! Unlike sampling a random distribution for each point, noise varies smoothly across the plane,
+//! so that neighboring points vary together. Use a [`NoiseField`] to turn a [`Noise`] into
+//! functions of the [`Context`](crate::shape::Context) that can be passed to shapes.
+mod field;
+mod fractal;
+mod open_simplex;
+mod perlin;
+mod simplex;
+mod warp;
+
+pub use field::NoiseField;
+pub use fractal::{Fbm, Ridged};
+pub use open_simplex::OpenSimplex;
+pub use perlin::Perlin;
+pub use simplex::Simplex;
+pub use warp::DomainWarp;
+
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+use crate::geometry::Coordinate;
+
+/// A trait for defining a 2D noise function
+pub trait Noise {
+    /// Sample the noise at a given `point`
+    ///
+    /// The returned value is roughly within `-1.0..=1.0`.
+    fn get(&self, point: Coordinate) -> f64;
+}
+
+impl<N: Noise + ?Sized> Noise for Box<N> {
+    fn get(&self, point: Coordinate) -> f64 {
+        (**self).get(point)
+    }
+}
+
+/// A permutation table shuffled using `seed`, repeated twice to avoid wrapping indices
+fn permutation_table(seed: u64) -> [u8; 512] {
+    let mut values: Vec<u8> = (0..=255).collect();
+    values.shuffle(&mut StdRng::seed_from_u64(seed));
+
+    let mut table = [0; 512];
+    for (i, value) in table.iter_mut().enumerate() {
+        *value = values[i % 256];
+    }
+
+    table
+}
+
+/// The quintic curve used to smooth interpolation between lattice points
+#[inline]
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
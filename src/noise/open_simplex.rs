@@ -0,0 +1,124 @@
+//! OpenSimplex noise
+use crate::{
+    geometry::Coordinate,
+    noise::{Noise, permutation_table},
+};
+
+/// Stretch constant, `(1 / √3 - 1) / 2`
+const STRETCH: f64 = -0.211_324_865_405_187;
+
+/// Squish constant, `(√3 - 1) / 2`
+const SQUISH: f64 = 0.366_025_403_784_439;
+
+/// Normalizes the result to `-1.0..=1.0`
+const NORM: f64 = 47.0;
+
+const GRADIENTS: [(f64, f64); 8] = [
+    (5.0, 2.0),
+    (2.0, 5.0),
+    (-5.0, 2.0),
+    (-2.0, 5.0),
+    (5.0, -2.0),
+    (2.0, -5.0),
+    (-5.0, -2.0),
+    (-2.0, -5.0),
+];
+
+/// OpenSimplex noise
+///
+/// An implementation of [Kurt Spencer's OpenSimplex noise](https://gist.github.com/KdotJPG/b1270127455a94ac5d19),
+/// which avoids the directional artifacts of [`Perlin`](crate::noise::Perlin) noise.
+#[derive(Debug, Clone)]
+pub struct OpenSimplex {
+    /// The seed used to shuffle the gradients
+    pub seed: u64,
+
+    permutation: [u8; 512],
+}
+
+impl OpenSimplex {
+    /// Create a new OpenSimplex noise function
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            permutation: permutation_table(seed),
+        }
+    }
+
+    fn contribution(&self, xsb: i64, ysb: i64, dx: f64, dy: f64) -> f64 {
+        let attn = 2.0 - dx * dx - dy * dy;
+        if attn <= 0.0 {
+            return 0.0;
+        }
+
+        let hash = self.permutation
+            [self.permutation[(xsb & 255) as usize] as usize + (ysb & 255) as usize];
+        let (gx, gy) = GRADIENTS[(hash & 7) as usize];
+
+        attn.powi(4) * (gx * dx + gy * dy)
+    }
+}
+
+impl Noise for OpenSimplex {
+    fn get(&self, point: Coordinate) -> f64 {
+        let (x, y) = point.to_cartesian();
+
+        // Place the point on the stretched simplectic honeycomb
+        let stretch_offset = (x + y) * STRETCH;
+        let (xs, ys) = (x + stretch_offset, y + stretch_offset);
+        let (mut xsb, mut ysb) = (xs.floor() as i64, ys.floor() as i64);
+
+        let squish_offset = (xsb + ysb) as f64 * SQUISH;
+        let (xb, yb) = (xsb as f64 + squish_offset, ysb as f64 + squish_offset);
+
+        let (xins, yins) = (xs - xsb as f64, ys - ysb as f64);
+        let in_sum = xins + yins;
+
+        let (mut dx0, mut dy0) = (x - xb, y - yb);
+
+        let mut value = self.contribution(xsb + 1, ysb, dx0 - 1.0 - SQUISH, dy0 - SQUISH)
+            + self.contribution(xsb, ysb + 1, dx0 - SQUISH, dy0 - 1.0 - SQUISH);
+
+        let (xsv_ext, ysv_ext, dx_ext, dy_ext);
+        if in_sum <= 1.0 {
+            let zins = 1.0 - in_sum;
+            if zins > xins || zins > yins {
+                if xins > yins {
+                    (xsv_ext, ysv_ext, dx_ext, dy_ext) = (xsb + 1, ysb - 1, dx0 - 1.0, dy0 + 1.0);
+                } else {
+                    (xsv_ext, ysv_ext, dx_ext, dy_ext) = (xsb - 1, ysb + 1, dx0 + 1.0, dy0 - 1.0);
+                }
+            } else {
+                (xsv_ext, ysv_ext, dx_ext, dy_ext) = (
+                    xsb + 1,
+                    ysb + 1,
+                    dx0 - 1.0 - 2.0 * SQUISH,
+                    dy0 - 1.0 - 2.0 * SQUISH,
+                );
+            }
+        } else {
+            let zins = 2.0 - in_sum;
+            if zins < xins || zins < yins {
+                if xins > yins {
+                    (xsv_ext, ysv_ext, dx_ext, dy_ext) =
+                        (xsb + 2, ysb, dx0 - 2.0 - 2.0 * SQUISH, dy0 - 2.0 * SQUISH);
+                } else {
+                    (xsv_ext, ysv_ext, dx_ext, dy_ext) =
+                        (xsb, ysb + 2, dx0 - 2.0 * SQUISH, dy0 - 2.0 - 2.0 * SQUISH);
+                }
+            } else {
+                (xsv_ext, ysv_ext, dx_ext, dy_ext) = (xsb, ysb, dx0, dy0);
+            }
+
+            xsb += 1;
+            ysb += 1;
+            dx0 -= 1.0 + 2.0 * SQUISH;
+            dy0 -= 1.0 + 2.0 * SQUISH;
+        }
+
+        value += self.contribution(xsb, ysb, dx0, dy0);
+        value += self.contribution(xsv_ext, ysv_ext, dx_ext, dy_ext);
+
+        (value / NORM).clamp(-1.0, 1.0)
+    }
+}
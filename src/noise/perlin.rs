@@ -0,0 +1,67 @@
+//! Perlin's improved gradient noise
+use std::f64::consts::SQRT_2;
+
+use crate::{
+    geometry::Coordinate,
+    noise::{Noise, fade, permutation_table},
+};
+
+/// Perlin's improved gradient noise
+///
+/// An implementation of [the algorithm described here](https://mrl.cs.nyu.edu/~perlin/paper445.pdf),
+/// restricted to 2D.
+#[derive(Debug, Clone)]
+pub struct Perlin {
+    /// The seed used to shuffle the gradients
+    pub seed: u64,
+
+    permutation: [u8; 512],
+}
+
+impl Perlin {
+    /// Create a new Perlin noise function
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            permutation: permutation_table(seed),
+        }
+    }
+
+    fn gradient(&self, x: usize, y: usize, dx: f64, dy: f64) -> f64 {
+        let hash = self.permutation[self.permutation[x] as usize + y];
+
+        match hash & 7 {
+            0 => dx + dy,
+            1 => -dx + dy,
+            2 => dx - dy,
+            3 => -dx - dy,
+            4 => SQRT_2 * dx,
+            5 => -SQRT_2 * dx,
+            6 => SQRT_2 * dy,
+            _ => -SQRT_2 * dy,
+        }
+    }
+}
+
+impl Noise for Perlin {
+    fn get(&self, point: Coordinate) -> f64 {
+        let (x, y) = point.to_cartesian();
+        let (x_floor, y_floor) = (x.floor(), y.floor());
+        let (dx, dy) = (x - x_floor, y - y_floor);
+
+        let xi = (x_floor as i64 & 255) as usize;
+        let yi = (y_floor as i64 & 255) as usize;
+
+        let n00 = self.gradient(xi, yi, dx, dy);
+        let n10 = self.gradient(xi + 1, yi, dx - 1.0, dy);
+        let n01 = self.gradient(xi, yi + 1, dx, dy - 1.0);
+        let n11 = self.gradient(xi + 1, yi + 1, dx - 1.0, dy - 1.0);
+
+        let (u, v) = (fade(dx), fade(dy));
+        let nx0 = n00 + u * (n10 - n00);
+        let nx1 = n01 + u * (n11 - n01);
+
+        // With gradients of length √2, the extremes are at ±1
+        (nx0 + v * (nx1 - nx0)).clamp(-1.0, 1.0)
+    }
+}
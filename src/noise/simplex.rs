@@ -0,0 +1,78 @@
+//! Simplex noise
+use crate::{
+    geometry::Coordinate,
+    noise::{Noise, permutation_table},
+};
+
+const GRADIENTS: [(f64, f64); 8] = [
+    (1.0, 1.0),
+    (-1.0, 1.0),
+    (1.0, -1.0),
+    (-1.0, -1.0),
+    (1.0, 0.0),
+    (-1.0, 0.0),
+    (0.0, 1.0),
+    (0.0, -1.0),
+];
+
+/// Simplex noise
+///
+/// An implementation of
+/// [the algorithm described here](https://weber.itn.liu.se/~stegu/simplexnoise/simplexnoise.pdf).
+/// Compared to [`Perlin`](crate::noise::Perlin) noise, it has fewer directional artifacts.
+#[derive(Debug, Clone)]
+pub struct Simplex {
+    /// The seed used to shuffle the gradients
+    pub seed: u64,
+
+    permutation: [u8; 512],
+}
+
+impl Simplex {
+    /// Create a new simplex noise function
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            permutation: permutation_table(seed),
+        }
+    }
+
+    fn corner(&self, x: usize, y: usize, dx: f64, dy: f64) -> f64 {
+        let t = 0.5 - dx * dx - dy * dy;
+        if t < 0.0 {
+            0.0
+        } else {
+            let (gx, gy) =
+                GRADIENTS[(self.permutation[x + self.permutation[y] as usize] & 7) as usize];
+            t.powi(4) * (gx * dx + gy * dy)
+        }
+    }
+}
+
+impl Noise for Simplex {
+    fn get(&self, point: Coordinate) -> f64 {
+        let skew = (3_f64.sqrt() - 1.0) / 2.0;
+        let unskew = (3.0 - 3_f64.sqrt()) / 6.0;
+
+        let (x, y) = point.to_cartesian();
+        let s = (x + y) * skew;
+        let (i, j) = ((x + s).floor(), (y + s).floor());
+        let t = (i + j) * unskew;
+        let (dx0, dy0) = (x - (i - t), y - (j - t));
+
+        // Determine which of the two triangles in the skewed cell the point is in
+        let (i1, j1) = if dx0 > dy0 { (1, 0) } else { (0, 1) };
+
+        let (dx1, dy1) = (dx0 - i1 as f64 + unskew, dy0 - j1 as f64 + unskew);
+        let (dx2, dy2) = (dx0 - 1.0 + 2.0 * unskew, dy0 - 1.0 + 2.0 * unskew);
+
+        let ii = (i as i64 & 255) as usize;
+        let jj = (j as i64 & 255) as usize;
+
+        let n = self.corner(ii, jj, dx0, dy0)
+            + self.corner(ii + i1, jj + j1, dx1, dy1)
+            + self.corner(ii + 1, jj + 1, dx2, dy2);
+
+        (70.0 * n).clamp(-1.0, 1.0)
+    }
+}
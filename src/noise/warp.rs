@@ -0,0 +1,33 @@
+//! Domain warped noise
+use crate::{geometry::Coordinate, noise::Noise};
+
+/// Noise where the input coordinate is displaced by another noise
+///
+/// This produces swirling, marbled patterns, similar to dye that has bled through folds of
+/// cloth. See [Inigo Quilez's article](https://iquilezles.org/articles/warp/) for details.
+#[derive(Debug, Clone, bon::Builder)]
+pub struct DomainWarp<N, W> {
+    /// The noise that is sampled at the displaced coordinate
+    pub source: N,
+
+    /// The noise used to displace the coordinate
+    pub warp: W,
+
+    /// How far the coordinate is displaced
+    #[builder(default = 1.0)]
+    pub strength: f64,
+}
+
+impl<N: Noise, W: Noise> Noise for DomainWarp<N, W> {
+    fn get(&self, point: Coordinate) -> f64 {
+        // Sample the warp at two distant offsets so that the x and y displacements are unrelated
+        let displacement = Coordinate::Cartesian {
+            x: self.warp.get(point),
+            y: self
+                .warp
+                .get(point + Coordinate::Cartesian { x: 5.2, y: 1.3 }),
+        };
+
+        self.source.get(point + displacement * self.strength)
+    }
+}
@@ -0,0 +1,109 @@
+//! The order the points of the [`Canvas`](crate::Canvas) are drawn in, from bottom to top
+use crate::shape::{Context, ContextFn, context::mix_seed};
+
+/// The number of bits for each axis of the Hilbert curve
+const HILBERT_ORDER: u32 = 16;
+
+/// The order that points are drawn in, which decides which shapes are on top where they overlap
+///
+/// Points that are drawn later are drawn on top. Orders that depend on where points are use
+/// their position on the canvas, after any layout and transform.
+#[derive(Default)]
+pub enum DrawOrder<I> {
+    /// The order of [`PointSet::index_iter`](crate::point_set::PointSet::index_iter)
+    #[default]
+    Index,
+
+    /// The reverse order of [`PointSet::index_iter`](crate::point_set::PointSet::index_iter)
+    Reversed,
+
+    /// From the top of the canvas to the bottom, so that lower shapes overlap those above them
+    /// like scales
+    TopToBottom,
+
+    /// From the bottom of the canvas to the top
+    BottomToTop,
+
+    /// From the center of the canvas outward
+    Outward,
+
+    /// From the edges of the canvas inward to the center
+    Inward,
+
+    /// A random order, which is the same for the same seed
+    Random(u64),
+
+    /// Along a Hilbert curve through the canvas, so that points drawn one after another are close
+    /// together
+    Hilbert,
+
+    /// From the lowest to the highest value of a key for each point
+    Custom(ContextFn<I, f64>),
+}
+
+impl<I> DrawOrder<I> {
+    /// Order the points by a key from the [`Context`] of each point, from lowest to highest
+    pub fn by_key(key: impl Fn(&Context<I>) -> f64 + 'static) -> Self {
+        DrawOrder::Custom(Box::new(key))
+    }
+
+    /// Sort `contexts` into the order, keeping the order of points with equal keys
+    pub(crate) fn sort(&self, contexts: Vec<Context<I>>) -> Vec<Context<I>> {
+        let key = |context: &Context<I>| -> f64 {
+            match self {
+                DrawOrder::Index | DrawOrder::Reversed => 0.0,
+                DrawOrder::TopToBottom => context.coordinate.to_cartesian().1,
+                DrawOrder::BottomToTop => -context.coordinate.to_cartesian().1,
+                DrawOrder::Outward => context.distance(),
+                DrawOrder::Inward => -context.distance(),
+                // Keep the bits that fit exactly in an `f64`
+                DrawOrder::Random(seed) => (mix_seed(*seed, context.seed) >> 11) as f64,
+                DrawOrder::Hilbert => hilbert_index(context.position()) as f64,
+                DrawOrder::Custom(key) => key(context),
+            }
+        };
+
+        match self {
+            DrawOrder::Index => contexts,
+            DrawOrder::Reversed => contexts.into_iter().rev().collect(),
+            _ => {
+                let mut keyed: Vec<(f64, Context<I>)> = contexts
+                    .into_iter()
+                    .map(|context| (key(&context), context))
+                    .collect();
+                keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                keyed.into_iter().map(|(_, context)| context).collect()
+            }
+        }
+    }
+}
+
+/// The distance along a Hilbert curve through the unit square of a normalized position
+///
+/// Positions outside of the square are moved to its nearest edge.
+fn hilbert_index((x, y): (f64, f64)) -> u64 {
+    let side = 1_u64 << HILBERT_ORDER;
+    let scale = |t: f64| ((t.clamp(0.0, 1.0) * (side - 1) as f64).round()) as u64;
+    let (mut x, mut y) = (scale(x), scale(y));
+
+    let mut index = 0;
+    let mut s = side / 2;
+    while 0 < s {
+        let rx = u64::from(x & s > 0);
+        let ry = u64::from(y & s > 0);
+        index += s * s * ((3 * rx) ^ ry);
+
+        // Rotate the quadrant so the curve continues from the previous one
+        if ry == 0 {
+            if rx == 1 {
+                x = side - 1 - x;
+                y = side - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+
+    index
+}
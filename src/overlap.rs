@@ -0,0 +1,373 @@
+//! Finding and resolving overlaps between shapes, such as for plotters where overlapping strokes
+//! tear the paper
+use std::f64::consts::PI;
+
+use svg::node::element::Path;
+
+use crate::{
+    geometry::{Angle, BezierPath, BoundingBox, Coordinate},
+    layout::Transform,
+};
+
+/// The approximate length of the line segments used to flatten curves
+const SEGMENT_LENGTH: f64 = 2.0;
+
+/// The maximum number of line segments used to flatten a single curve
+const MAX_SEGMENTS: usize = 64;
+
+/// The number of smaller sizes or nearby positions tried before a shape is dropped
+const ATTEMPTS: usize = 8;
+
+/// How a [`Layer`](crate::Layer) resolves shapes that overlap shapes drawn before them
+///
+/// Overlaps are found from the geometry of the shapes, including the width of their strokes and
+/// how far any effects reach, rather than their bounding boxes. Shapes are only tested against
+/// shapes from layers that also resolve overlaps, so layers left at [`Overlap::Allow`] are free
+/// to overlap anything.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Overlap {
+    /// Draw every shape, even where they overlap
+    #[default]
+    Allow,
+
+    /// Leave out shapes that overlap
+    Drop,
+
+    /// Shrink shapes that overlap, down to `min_scale` of their size, or leave them out if they
+    /// still overlap
+    Shrink { min_scale: f64 },
+
+    /// Move shapes that overlap by up to `distance`, or leave them out if they still overlap
+    Nudge { distance: f64 },
+}
+
+impl Overlap {
+    /// The scale and offset of each placement to try for a shape, in order
+    pub(crate) fn placements(&self) -> Vec<(f64, Coordinate)> {
+        let identity = (1.0, Coordinate::origin());
+
+        match *self {
+            Overlap::Allow | Overlap::Drop => vec![identity],
+            Overlap::Shrink { min_scale } => {
+                let min_scale = min_scale.clamp(0.0, 1.0);
+                (0..=ATTEMPTS)
+                    .map(|i| {
+                        let scale = 1.0 - (1.0 - min_scale) * i as f64 / ATTEMPTS as f64;
+                        (scale, Coordinate::origin())
+                    })
+                    .filter(|(scale, _)| 0.0 < *scale)
+                    .collect()
+            }
+            Overlap::Nudge { distance } => {
+                // Try each direction at a short distance, before moving further away
+                let rings = ATTEMPTS / 2;
+                std::iter::once(identity)
+                    .chain((1..=rings).flat_map(|ring| {
+                        let r = distance * ring as f64 / rings as f64;
+                        (0..ATTEMPTS).map(move |k| {
+                            let phi = Angle::Radian(2.0 * PI * k as f64 / ATTEMPTS as f64);
+                            (1.0, Coordinate::Polar { r, phi })
+                        })
+                    }))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// The area covered by a shape on the canvas, used to test for overlaps
+#[derive(Debug, Clone)]
+pub(crate) struct Footprint {
+    /// The lines of the outline, where closed contours end back at their start
+    lines: Vec<Vec<Coordinate>>,
+
+    /// Whether the area inside the outline is filled
+    filled: bool,
+
+    /// Half the width of the stroke, or `0.0` if the outline isn't stroked
+    radius: f64,
+
+    /// The bounding box of the lines, including the stroke
+    bb: BoundingBox,
+}
+
+impl Footprint {
+    /// Find the footprint of an SVG `path`, from its outline, fill and stroke
+    pub(crate) fn from_path(path: &Path) -> Self {
+        let attributes = path.get_attributes();
+        let attribute = |name: &str| attributes.get(name).map(|value| value.to_string());
+
+        let filled = attribute("fill").is_none_or(|fill| fill != "none");
+        let radius = match attribute("stroke") {
+            Some(stroke) if stroke != "none" => {
+                attribute("stroke-width")
+                    .and_then(|width| width.parse::<f64>().ok())
+                    .unwrap_or(1.0)
+                    / 2.0
+            }
+            _ => 0.0,
+        };
+
+        let mut lines = BezierPath::from_path(path).flatten_lines(SEGMENT_LENGTH, MAX_SEGMENTS);
+        if filled {
+            // The fill is closed, even where the stroke isn't
+            for line in &mut lines {
+                if let (Some(first), Some(last)) = (line.first().copied(), line.last())
+                    && first.dist(last) > 0.0
+                {
+                    line.push(first);
+                }
+            }
+        }
+
+        Self::new(lines, filled, radius)
+    }
+
+    fn new(lines: Vec<Vec<Coordinate>>, filled: bool, radius: f64) -> Self {
+        let bb = BoundingBox::from_points(lines.iter().flatten());
+        let bb = if bb.is_empty() {
+            bb
+        } else {
+            let margin = Coordinate::Cartesian {
+                x: radius,
+                y: radius,
+            };
+            BoundingBox::new(bb.min() - margin, bb.max() + margin)
+        };
+
+        Self {
+            lines,
+            filled,
+            radius,
+            bb,
+        }
+    }
+
+    /// Grow the footprint evenly until its bounding box contains `bb`
+    ///
+    /// This keeps what is drawn around the outline of a shape, such as a blur or a shadow, from
+    /// overlapping other shapes.
+    pub(crate) fn grow_to(mut self, bb: BoundingBox) -> Self {
+        if self.bb.is_empty() || bb.is_empty() {
+            return self;
+        }
+
+        let (below_x, below_y) = (self.bb.min() - bb.min()).to_cartesian();
+        let (above_x, above_y) = (bb.max() - self.bb.max()).to_cartesian();
+        let margin = [below_x, below_y, above_x, above_y]
+            .into_iter()
+            .fold(0.0, f64::max);
+        if margin == 0.0 {
+            return self;
+        }
+
+        self.radius += margin;
+        Self::new(self.lines, self.filled, self.radius)
+    }
+
+    /// Scale the footprint by `scale` around the origin, then move it by `offset`
+    pub(crate) fn transform(&self, scale: f64, offset: Coordinate) -> Self {
+        Self::new(
+            self.lines
+                .iter()
+                .map(|line| line.iter().map(|point| *point * scale + offset).collect())
+                .collect(),
+            self.filled,
+            self.radius * scale,
+        )
+    }
+
+    /// Rotate or mirror the footprint around the origin by `transform`
+    pub(crate) fn apply(&self, transform: &Transform) -> Self {
+        Self::new(
+            self.lines
+                .iter()
+                .map(|line| line.iter().map(|point| transform.apply(*point)).collect())
+                .collect(),
+            self.filled,
+            self.radius,
+        )
+    }
+
+    /// Whether the footprint covers any of the same area as `other`
+    ///
+    /// Shapes that only touch don't overlap.
+    pub(crate) fn overlaps(&self, other: &Self) -> bool {
+        if self.bb.is_empty() || other.bb.is_empty() || !self.bb.intersects(&other.bb) {
+            return false;
+        }
+
+        if self.contains_any(other) || other.contains_any(self) {
+            return true;
+        }
+
+        // Only segments whose stroke reaches the other footprint can meet it
+        let reach = self.radius + other.radius;
+        let near = |footprint: &Self, bb: BoundingBox| {
+            let margin = Coordinate::Cartesian {
+                x: footprint.radius,
+                y: footprint.radius,
+            };
+            footprint
+                .segments()
+                .filter(|(start, end)| {
+                    let segment_bb = BoundingBox::new(*start, *end);
+                    BoundingBox::new(segment_bb.min() - margin, segment_bb.max() + margin)
+                        .intersects(&bb)
+                })
+                .collect::<Vec<_>>()
+        };
+        let segments = near(self, other.bb);
+        let other_segments = near(other, self.bb);
+
+        segments.iter().any(|a| {
+            other_segments
+                .iter()
+                .any(|b| segments_cross(*a, *b) || segment_distance(*a, *b) < reach)
+        })
+    }
+
+    /// Whether the filled area contains a point of `other`
+    ///
+    /// This only tests the first point of each line, as a line that is partly inside also meets
+    /// the outline.
+    fn contains_any(&self, other: &Self) -> bool {
+        self.filled
+            && other
+                .lines
+                .iter()
+                .filter_map(|line| line.first())
+                .any(|point| self.contains(*point))
+    }
+
+    /// Whether `point` is inside the filled area, following the even-odd rule
+    fn contains(&self, point: Coordinate) -> bool {
+        let (x, y) = point.to_cartesian();
+
+        self.segments()
+            .filter(|(start, end)| {
+                let (x1, y1) = start.to_cartesian();
+                let (x2, y2) = end.to_cartesian();
+                (y1 > y) != (y2 > y) && x < x1 + (y - y1) / (y2 - y1) * (x2 - x1)
+            })
+            .count()
+            % 2
+            == 1
+    }
+
+    fn segments(&self) -> impl Iterator<Item = (Coordinate, Coordinate)> + '_ {
+        self.lines.iter().flat_map(|line| {
+            // A single point is drawn as a dot by a stroke
+            let dot = (line.len() == 1).then(|| (line[0], line[0]));
+            dot.into_iter()
+                .chain(line.windows(2).map(|pair| (pair[0], pair[1])))
+        })
+    }
+}
+
+/// The cross product of `b - a` and `c - a`, which is positive if `c` is clockwise of `b`
+fn cross(a: Coordinate, b: Coordinate, c: Coordinate) -> f64 {
+    let (x1, y1) = (b - a).to_cartesian();
+    let (x2, y2) = (c - a).to_cartesian();
+    x1 * y2 - y1 * x2
+}
+
+/// Whether two segments properly cross, rather than only touching
+fn segments_cross((a1, a2): (Coordinate, Coordinate), (b1, b2): (Coordinate, Coordinate)) -> bool {
+    let d1 = cross(b1, b2, a1);
+    let d2 = cross(b1, b2, a2);
+    let d3 = cross(a1, a2, b1);
+    let d4 = cross(a1, a2, b2);
+
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+/// The shortest distance between two segments that don't cross
+fn segment_distance(a: (Coordinate, Coordinate), b: (Coordinate, Coordinate)) -> f64 {
+    [
+        point_segment_distance(a.0, b),
+        point_segment_distance(a.1, b),
+        point_segment_distance(b.0, a),
+        point_segment_distance(b.1, a),
+    ]
+    .into_iter()
+    .fold(f64::INFINITY, f64::min)
+}
+
+fn point_segment_distance(point: Coordinate, (start, end): (Coordinate, Coordinate)) -> f64 {
+    let (dx, dy) = (end - start).to_cartesian();
+    let (px, py) = (point - start).to_cartesian();
+    let length_squared = dx * dx + dy * dy;
+
+    let t = if length_squared > 0.0 {
+        ((px * dx + py * dy) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    point.dist(&(start + Coordinate::Cartesian { x: dx, y: dy } * t))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<Coordinate> {
+        [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)]
+            .map(|(u, v)| Coordinate::Cartesian {
+                x: x + u * size,
+                y: y + v * size,
+            })
+            .to_vec()
+    }
+
+    fn filled(x: f64, y: f64, size: f64) -> Footprint {
+        Footprint::new(vec![square(x, y, size)], true, 0.0)
+    }
+
+    fn stroked(x: f64, y: f64, size: f64, width: f64) -> Footprint {
+        Footprint::new(vec![square(x, y, size)], false, width / 2.0)
+    }
+
+    #[test]
+    fn separate_shapes_do_not_overlap() {
+        assert!(!filled(0.0, 0.0, 10.0).overlaps(&filled(20.0, 0.0, 10.0)));
+    }
+
+    #[test]
+    fn touching_shapes_do_not_overlap() {
+        assert!(!filled(0.0, 0.0, 10.0).overlaps(&filled(10.0, 0.0, 10.0)));
+    }
+
+    #[test]
+    fn crossing_shapes_overlap() {
+        assert!(filled(0.0, 0.0, 10.0).overlaps(&filled(5.0, 5.0, 10.0)));
+    }
+
+    #[test]
+    fn filled_shapes_overlap_shapes_inside_them() {
+        assert!(filled(0.0, 0.0, 10.0).overlaps(&filled(2.0, 2.0, 2.0)));
+        assert!(filled(2.0, 2.0, 2.0).overlaps(&filled(0.0, 0.0, 10.0)));
+
+        // An outline leaves its inside empty
+        assert!(!stroked(0.0, 0.0, 10.0, 1.0).overlaps(&stroked(2.0, 2.0, 2.0, 1.0)));
+    }
+
+    #[test]
+    fn strokes_overlap_within_their_width() {
+        assert!(!stroked(0.0, 0.0, 10.0, 1.0).overlaps(&stroked(12.0, 0.0, 10.0, 1.0)));
+        assert!(stroked(0.0, 0.0, 10.0, 4.0).overlaps(&stroked(12.0, 0.0, 10.0, 1.0)));
+    }
+
+    #[test]
+    fn grown_footprints_contain_the_bb() {
+        let bb = BoundingBox::new(
+            Coordinate::Cartesian { x: -3.0, y: 0.0 },
+            Coordinate::Cartesian { x: 10.0, y: 10.0 },
+        );
+        let footprint = filled(0.0, 0.0, 10.0).grow_to(bb);
+
+        assert_eq!(footprint.radius, 3.0);
+        assert!(footprint.overlaps(&filled(12.0, 0.0, 10.0)));
+    }
+}
@@ -6,11 +6,15 @@ use itertools::iproduct;
 
 use crate::{
     geometry::{Angle, BoundingBox, Coordinate},
-    point_set::PointSet,
+    point_set::{
+        PointSet,
+        neighbors::{self, Edges, Neighborhood},
+    },
+    tile::Repeat,
 };
 
 /// The index for each point in the lattice
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Index {
     pub u: u16,
     pub v: u16,
@@ -115,6 +119,71 @@ impl Lattice {
     ) -> Self {
         Self::new(grid_size, len_a, len_a, Angle::Radian(PI / 3.0))
     }
+
+    /// Change the `grid_size` so that the lattice covers an area of `width` by `height`
+    ///
+    /// Once centered in the area, every row reaches past both sides and the rows reach past the
+    /// top and bottom, so that shapes on the lattice fill a [`Canvas`](crate::Canvas) of that size.
+    pub fn fill(mut self, width: f64, height: f64) -> Self {
+        // Every other row is shifted along `a`, so the rows need to be that much wider
+        let row_shift = (self.len_b * self.theta_cos).abs();
+        let row_spacing = self.len_b * self.theta_sin;
+
+        let count = |length: f64, spacing: f64| {
+            ((length / spacing).ceil() + 1.0).clamp(1.0, f64::from(u16::MAX)) as u16
+        };
+        self.grid_size = (
+            count(width + row_shift, self.len_a),
+            count(height, row_spacing),
+        );
+
+        self
+    }
+
+    /// Snap the spacing of the lattice so that it tiles an area of `width` by `height` with
+    /// `repeat`
+    ///
+    /// The number of columns and rows are rounded to fit the area, and `a` and `b` are stretched
+    /// to match, so the points continue evenly across the edges. When every other row is shifted,
+    /// the number of rows is kept even, as is the number of rows or columns in each half of the
+    /// tile for a half-drop or brick repeat.
+    pub fn tile(self, width: f64, height: f64, repeat: Repeat) -> Self {
+        let shift = self.len_b * self.theta_cos;
+        let row_spacing = self.len_b * self.theta_sin;
+        let has_shifted_rows = 1e-9 < (shift / self.len_a).abs();
+
+        let count = |length: f64, spacing: f64, multiple: f64| {
+            (length / spacing / multiple)
+                .round()
+                .clamp(1.0, f64::from(u16::MAX) / multiple)
+                .floor()
+                * multiple
+        };
+        let columns = count(
+            width,
+            self.len_a,
+            if repeat == Repeat::Brick { 2.0 } else { 1.0 },
+        );
+        let rows = count(
+            height,
+            row_spacing,
+            match (has_shifted_rows, repeat == Repeat::HalfDrop) {
+                (true, true) => 4.0,
+                (true, false) | (false, true) => 2.0,
+                (false, false) => 1.0,
+            },
+        );
+
+        let len_a = width / columns;
+        let (b_x, b_y) = (shift * len_a / self.len_a, height / rows);
+
+        Self::new(
+            (columns as u16, rows as u16),
+            len_a,
+            b_x.hypot(b_y),
+            Angle::Radian(b_y.atan2(b_x)),
+        )
+    }
 }
 
 impl PointSet for Lattice {
@@ -132,6 +201,56 @@ impl PointSet for Lattice {
         Coordinate::Cartesian { x, y }
     }
 
+    fn count(&self) -> usize {
+        usize::from(self.grid_size.0) * usize::from(self.grid_size.1)
+    }
+
+    /// A copy of the lattice with its `grid_size` changed to cover the area, as with
+    /// [`Lattice::fill`]
+    fn resize(&self, width: f64, height: f64) -> Option<Self> {
+        Some(self.fill(width, height))
+    }
+
+    /// A copy of the lattice with its spacing snapped to the tile, as with [`Lattice::tile`]
+    fn tile(&self, width: f64, height: f64, repeat: Repeat) -> Option<Self> {
+        Some(Lattice::tile(*self, width, height, repeat))
+    }
+
+    /// Find the pairs of neighboring points, with each pair listed once
+    ///
+    /// [`Neighborhood::Adjacent`] joins each point to the next point in its row, and to the points
+    /// in the next row that are closest to it.
+    fn edges(&self, neighborhood: Neighborhood) -> Edges<Self::Index> {
+        let Neighborhood::Adjacent = neighborhood else {
+            return neighbors::edges_by_coordinate(self, neighborhood);
+        };
+
+        let (columns, rows) = self.grid_size;
+        let pairs = self.index_iter().flat_map(|Index { u, v }| {
+            // Odd rows are shifted along `a`, so which points in the next row are closest
+            // depends on the shift and the parity of the row
+            let next_row_shift = if self.theta_cos.abs() < 1e-9 {
+                None
+            } else if (self.theta_cos > 0.0) == (v % 2 == 0) {
+                u.checked_sub(1)
+            } else {
+                Some(u + 1)
+            };
+
+            [
+                Some(Index { u: u + 1, v }),
+                Some(Index { u, v: v + 1 }),
+                next_row_shift.map(|u| Index { u, v: v + 1 }),
+            ]
+            .into_iter()
+            .flatten()
+            .filter(move |neighbor| neighbor.u < columns && neighbor.v < rows)
+            .map(move |neighbor| (Index { u, v }, neighbor))
+        });
+
+        Edges::from_pairs(self, pairs.collect::<Vec<_>>())
+    }
+
     fn bounding_box(&self) -> BoundingBox {
         let max_x = f64::from(self.grid_size.0 - 1) * self.len_a
             + if 2 <= self.grid_size.1 {
@@ -147,3 +266,64 @@ impl PointSet for Lattice {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    /// The number of neighbors of each point, checking that every edge is `len` long
+    fn degrees(lattice: &Lattice, len: f64) -> HashMap<Index, usize> {
+        let mut degrees = HashMap::new();
+        for edge in lattice.edges(Neighborhood::Adjacent).iter() {
+            assert!(
+                (edge.vector.dist(&Coordinate::origin()) - len).abs() < 1e-9,
+                "{edge:?}"
+            );
+            *degrees.entry(edge.from).or_default() += 1;
+            *degrees.entry(edge.to).or_default() += 1;
+        }
+        degrees
+    }
+
+    #[test]
+    fn square_lattices_join_rows_and_columns() {
+        for (columns, rows) in [(4, 3), (4, 4), (3, 5)] {
+            let lattice = Lattice::square_builder()
+                .grid_size(columns, rows)
+                .len_a(10.0)
+                .build();
+            let (columns, rows) = (usize::from(columns), usize::from(rows));
+
+            assert_eq!(
+                lattice.edges(Neighborhood::Adjacent).len(),
+                (columns - 1) * rows + columns * (rows - 1)
+            );
+            assert_eq!(degrees(&lattice, 10.0)[&Index { u: 1, v: 1 }], 4);
+        }
+    }
+
+    #[test]
+    fn hexagonal_lattices_join_six_neighbors_in_odd_and_even_rows() {
+        for (columns, rows) in [(5, 3), (5, 4)] {
+            let lattice = Lattice::hexagonal_builder()
+                .grid_size(columns, rows)
+                .len_a(10.0)
+                .build();
+            let (c, r) = (usize::from(columns), usize::from(rows));
+
+            assert_eq!(
+                lattice.edges(Neighborhood::Adjacent).len(),
+                (c - 1) * r + c * (r - 1) + (c - 1) * (r - 1)
+            );
+
+            let degrees = degrees(&lattice, 10.0);
+            for v in 1..rows - 1 {
+                for u in 1..columns - 1 {
+                    assert_eq!(degrees[&Index { u, v }], 6, "at {u}, {v}");
+                }
+            }
+        }
+    }
+}
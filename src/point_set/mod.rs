@@ -1,8 +1,14 @@
 pub mod lattice;
+pub mod neighbors;
+pub mod packing;
 pub mod poisson_disk;
 pub mod vogel;
 
-use crate::geometry::{BoundingBox, Coordinate};
+use crate::{
+    geometry::{BoundingBox, Coordinate},
+    tile::Repeat,
+};
+use neighbors::{Edges, Neighborhood};
 
 /// A trait for putting points on a 2D plane.
 ///
@@ -19,8 +25,52 @@ pub trait PointSet {
     /// This can be independent of the coordinates in the `Canvas`.
     fn index_to_coordinate(&self, index: &Self::Index) -> Coordinate;
 
+    /// The number of points in the set
+    fn count(&self) -> usize {
+        self.index_iter().count()
+    }
+
     /// The size of the box that would contain all coordinates for the set
     ///
     /// This is used to center the `PointSet` within the `Canvas`.
     fn bounding_box(&self) -> BoundingBox;
+
+    /// A copy of the set, grown or shrunk to cover an area of `width` by `height`
+    ///
+    /// The copy is the same each time for the same area, so that data such as
+    /// [`Packing`](packing::Packing) sizes can be found for the copy the
+    /// [`Canvas`](crate::Canvas) draws, which is given by
+    /// [`Canvas::resized_points`](crate::Canvas::resized_points). This is `None` for sets that can't be resized, which is
+    /// the default.
+    fn resize(&self, width: f64, height: f64) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let _ = (width, height);
+        None
+    }
+
+    /// A copy of the set made to tile an area of `width` by `height` seamlessly with `repeat`
+    ///
+    /// The points of the copy continue across the edges of the area into the neighboring tiles.
+    /// As with [`PointSet::resize`], the copy is the same each time for the same arguments. This
+    /// is `None` for sets that can't be tiled, which is the default.
+    fn tile(&self, width: f64, height: f64, repeat: Repeat) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let _ = (width, height, repeat);
+        None
+    }
+
+    /// Find the pairs of neighboring points, with each pair listed once
+    ///
+    /// By default, the neighbors are found from the coordinates of the points using a
+    /// [`SpatialIndex`](neighbors::SpatialIndex), or a Delaunay triangulation.
+    fn edges(&self, neighborhood: Neighborhood) -> Edges<Self::Index>
+    where
+        Self::Index: Clone,
+    {
+        neighbors::edges_by_coordinate(self, neighborhood)
+    }
 }
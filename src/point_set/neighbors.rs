@@ -0,0 +1,506 @@
+//! Finding the neighbors of points
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{geometry::Coordinate, point_set::PointSet};
+
+/// How the neighbors of a point are chosen
+#[derive(Debug, Clone, Copy)]
+pub enum Neighborhood {
+    /// The `k` nearest points
+    Nearest(usize),
+
+    /// All points within a distance
+    Within(f64),
+
+    /// Points joined by an edge of the
+    /// [Delaunay triangulation](https://en.wikipedia.org/wiki/Delaunay_triangulation)
+    Delaunay,
+
+    /// Points next to each other in the structure of the point set
+    ///
+    /// For a [`Lattice`](crate::point_set::lattice::Lattice) these are the points in the same row
+    /// or the rows above and below, such as `Index { u ± 1, v ± 1 }`. Point sets without such a
+    /// structure use [`Neighborhood::Delaunay`].
+    Adjacent,
+}
+
+impl Neighborhood {
+    /// Find the pairs of neighboring points by their position in `points`
+    ///
+    /// Each pair is listed once, with the smaller position first.
+    pub fn pairs(&self, points: &[Coordinate]) -> Vec<(usize, usize)> {
+        let pairs: BTreeSet<_> = match *self {
+            Neighborhood::Nearest(k) => {
+                let index = SpatialIndex::new(points.to_vec());
+                points
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, point)| {
+                        index
+                            .nearest(*point, k + 1)
+                            .into_iter()
+                            .filter(move |j| *j != i)
+                            .take(k)
+                            .map(move |j| (i.min(j), i.max(j)))
+                    })
+                    .collect()
+            }
+            Neighborhood::Within(radius) => {
+                let index = SpatialIndex::new(points.to_vec());
+                points
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, point)| {
+                        index
+                            .within(*point, radius)
+                            .into_iter()
+                            .filter(move |j| i < *j)
+                            .map(move |j| (i, j))
+                    })
+                    .collect()
+            }
+            Neighborhood::Delaunay | Neighborhood::Adjacent => {
+                delaunay(points).into_iter().collect()
+            }
+        };
+
+        pairs.into_iter().collect()
+    }
+}
+
+/// A pair of neighboring points
+#[derive(Debug, Clone)]
+pub struct Edge<I> {
+    pub from: I,
+    pub to: I,
+
+    /// The vector from the point at `from` to the point at `to`
+    pub vector: Coordinate,
+}
+
+/// A list of pairs of neighboring points, with each pair listed once
+#[derive(Debug, Clone)]
+pub struct Edges<I> {
+    pub edges: Vec<Edge<I>>,
+}
+
+impl<I> Edges<I> {
+    /// Define the edges between pairs of indices of `points`
+    pub fn from_pairs<P: PointSet<Index = I>>(
+        points: &P,
+        pairs: impl IntoIterator<Item = (I, I)>,
+    ) -> Self {
+        Self {
+            edges: pairs
+                .into_iter()
+                .map(|(from, to)| {
+                    let vector =
+                        points.index_to_coordinate(&to) - points.index_to_coordinate(&from);
+                    Edge { from, to, vector }
+                })
+                .collect(),
+        }
+    }
+
+    /// The number of edges
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Whether there are no edges
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Iterate through the edges
+    pub fn iter(&self) -> impl Iterator<Item = &Edge<I>> {
+        self.edges.iter()
+    }
+}
+
+/// Find the edges of `points` using their coordinates
+pub(crate) fn edges_by_coordinate<P>(points: &P, neighborhood: Neighborhood) -> Edges<P::Index>
+where
+    P: PointSet + ?Sized,
+    P::Index: Clone,
+{
+    let indices: Vec<_> = points.index_iter().collect();
+    let coordinates: Vec<_> = indices
+        .iter()
+        .map(|index| points.index_to_coordinate(index))
+        .collect();
+
+    Edges {
+        edges: neighborhood
+            .pairs(&coordinates)
+            .into_iter()
+            .map(|(from, to)| Edge {
+                from: indices[from].clone(),
+                to: indices[to].clone(),
+                vector: coordinates[to] - coordinates[from],
+            })
+            .collect(),
+    }
+}
+
+/// A grid of buckets for quickly finding points near a coordinate
+///
+/// This can be used to find neighbors in any set of points, such as those of a custom
+/// [`PointSet`].
+#[derive(Debug, Clone)]
+pub struct SpatialIndex {
+    points: Vec<Coordinate>,
+    cell_size: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    min_cell: (i64, i64),
+    max_cell: (i64, i64),
+}
+
+impl SpatialIndex {
+    /// Build the index for `points`
+    pub fn new(points: Vec<Coordinate>) -> Self {
+        let (min_x, min_y, max_x, max_y) = points.iter().fold(
+            (
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            |(min_x, min_y, max_x, max_y), point| {
+                let (x, y) = point.to_cartesian();
+                (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+            },
+        );
+
+        // Aim for around one point per cell
+        let area = ((max_x - min_x) * (max_y - min_y)).max(0.0);
+        let cell_size = (area / points.len().max(1) as f64).sqrt();
+        let cell_size = if cell_size.is_finite() && cell_size > 0.0 {
+            cell_size
+        } else {
+            ((max_x - min_x).max(max_y - min_y) / points.len().max(1) as f64).max(f64::MIN_POSITIVE)
+        };
+
+        let mut index = Self {
+            points: Vec::new(),
+            cell_size,
+            cells: HashMap::new(),
+            min_cell: (0, 0),
+            max_cell: (0, 0),
+        };
+        for (i, point) in points.iter().enumerate() {
+            index.cells.entry(index.cell(*point)).or_default().push(i);
+        }
+        if !points.is_empty() {
+            index.min_cell = index.cell(Coordinate::Cartesian { x: min_x, y: min_y });
+            index.max_cell = index.cell(Coordinate::Cartesian { x: max_x, y: max_y });
+        }
+        index.points = points;
+
+        index
+    }
+
+    fn cell(&self, point: Coordinate) -> (i64, i64) {
+        let (x, y) = point.to_cartesian();
+        (
+            (x / self.cell_size).floor() as i64,
+            (y / self.cell_size).floor() as i64,
+        )
+    }
+
+    /// The number of rings around `center` needed to cover every cell with points
+    fn max_ring(&self, center: (i64, i64)) -> i64 {
+        [
+            center.0 - self.min_cell.0,
+            self.max_cell.0 - center.0,
+            center.1 - self.min_cell.1,
+            self.max_cell.1 - center.1,
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or_default()
+        .max(0)
+    }
+
+    /// The positions of the points in the cells `ring` cells away from `center`
+    fn ring(&self, center: (i64, i64), ring: i64) -> impl Iterator<Item = usize> + '_ {
+        let (cx, cy) = center;
+        (-ring..=ring)
+            .flat_map(move |dx| (-ring..=ring).map(move |dy| (dx, dy)))
+            .filter(move |(dx, dy)| dx.abs() == ring || dy.abs() == ring)
+            .filter_map(move |(dx, dy)| self.cells.get(&(cx + dx, cy + dy)))
+            .flatten()
+            .copied()
+    }
+
+    /// The positions of all points within `radius` of `point`
+    pub fn within(&self, point: Coordinate, radius: f64) -> Vec<usize> {
+        let center = self.cell(point);
+        let rings = ((radius / self.cell_size).floor() as i64 + 1).min(self.max_ring(center));
+
+        (0..=rings)
+            .flat_map(|ring| self.ring(center, ring))
+            .filter(|i| self.points[*i].dist(&point) <= radius)
+            .collect()
+    }
+
+    /// The positions of the `k` nearest points to `point`, from nearest to furthest
+    pub fn nearest(&self, point: Coordinate, k: usize) -> Vec<usize> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let center = self.cell(point);
+        let mut found: Vec<(f64, usize)> = Vec::new();
+
+        for ring in 0..=self.max_ring(center) {
+            found.extend(
+                self.ring(center, ring)
+                    .map(|i| (self.points[i].dist(&point), i)),
+            );
+
+            // Points in further rings are at least `ring` cells away
+            if k <= found.len() {
+                found.sort_by(|a, b| a.0.total_cmp(&b.0));
+                if found[k - 1].0 <= ring as f64 * self.cell_size {
+                    break;
+                }
+            }
+        }
+
+        found.sort_by(|a, b| a.0.total_cmp(&b.0));
+        found.into_iter().take(k).map(|(_, i)| i).collect()
+    }
+}
+
+/// The edges of the Delaunay triangulation of `points`, using the Bowyer-Watson algorithm
+///
+/// Each edge is listed once, with the smaller position first.
+pub fn delaunay(points: &[Coordinate]) -> Vec<(usize, usize)> {
+    if points.len() < 2 {
+        return Vec::new();
+    }
+
+    let cartesian: Vec<_> = points.iter().map(|point| point.to_cartesian()).collect();
+    let (min_x, min_y, max_x, max_y) = cartesian.iter().fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(min_x, min_y, max_x, max_y), &(x, y)| {
+            (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+        },
+    );
+    let span = (max_x - min_x).max(max_y - min_y).max(1.0);
+    let (mid_x, mid_y) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+
+    // A triangle enclosing every point, whose vertices are removed at the end
+    let n = points.len();
+    let mut vertices = cartesian.clone();
+    vertices.push((mid_x - 20.0 * span, mid_y - span));
+    vertices.push((mid_x, mid_y + 20.0 * span));
+    vertices.push((mid_x + 20.0 * span, mid_y - span));
+
+    let mut triangles = vec![Triangle::new(&vertices, [n, n + 1, n + 2])];
+
+    for i in 0..n {
+        let (x, y) = vertices[i];
+        let (bad, good): (Vec<_>, Vec<_>) = triangles
+            .into_iter()
+            .partition(|triangle| triangle.circumcircle_contains(x, y));
+        triangles = good;
+
+        // The boundary of the hole left by the bad triangles is made of their unshared edges
+        let mut edge_counts: HashMap<(usize, usize), usize> = HashMap::new();
+        for triangle in &bad {
+            for edge in triangle.edges() {
+                *edge_counts.entry(edge).or_default() += 1;
+            }
+        }
+
+        triangles.extend(
+            edge_counts
+                .into_iter()
+                .filter(|(_, count)| *count == 1)
+                .map(|((a, b), _)| Triangle::new(&vertices, [a, b, i])),
+        );
+    }
+
+    let edges: BTreeSet<_> = triangles
+        .iter()
+        .flat_map(Triangle::edges)
+        .filter(|&(a, b)| a < n && b < n)
+        .collect();
+
+    edges.into_iter().collect()
+}
+
+/// A triangle of the triangulation, with its circumcircle
+struct Triangle {
+    vertices: [usize; 3],
+    center: (f64, f64),
+    radius_squared: f64,
+}
+
+impl Triangle {
+    fn new(points: &[(f64, f64)], vertices: [usize; 3]) -> Self {
+        let [(ax, ay), (bx, by), (cx, cy)] = vertices.map(|i| points[i]);
+        let d = 2.0 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
+        let (a2, b2, c2) = (ax * ax + ay * ay, bx * bx + by * by, cx * cx + cy * cy);
+        let center = (
+            (a2 * (by - cy) + b2 * (cy - ay) + c2 * (ay - by)) / d,
+            (a2 * (cx - bx) + b2 * (ax - cx) + c2 * (bx - ax)) / d,
+        );
+
+        // Degenerate triangles have no finite circumcircle, so any point replaces them
+        let radius_squared = if d == 0.0 {
+            f64::INFINITY
+        } else {
+            (ax - center.0).powi(2) + (ay - center.1).powi(2)
+        };
+
+        Self {
+            vertices,
+            center,
+            radius_squared,
+        }
+    }
+
+    fn circumcircle_contains(&self, x: f64, y: f64) -> bool {
+        self.radius_squared.is_infinite()
+            || (x - self.center.0).powi(2) + (y - self.center.1).powi(2) < self.radius_squared
+    }
+
+    /// The edges of the triangle, with the smaller vertex first
+    fn edges(&self) -> [(usize, usize); 3] {
+        let [a, b, c] = self.vertices;
+        [
+            (a.min(b), a.max(b)),
+            (b.min(c), b.max(c)),
+            (a.min(c), a.max(c)),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{RngExt, SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    fn point(x: f64, y: f64) -> Coordinate {
+        Coordinate::Cartesian { x, y }
+    }
+
+    /// Random points, with some clustered together and some repeated
+    fn scattered(seed: u64) -> Vec<Coordinate> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut points: Vec<_> = (0..200)
+            .map(|_| point(rng.random_range(0.0..100.0), rng.random_range(0.0..50.0)))
+            .collect();
+        points.extend(
+            (0..20).map(|_| point(rng.random_range(70.0..71.0), rng.random_range(0.0..1.0))),
+        );
+        points.extend_from_within(..10);
+        points
+    }
+
+    #[test]
+    fn delaunay_joins_a_square_to_its_center() {
+        let points = [
+            point(0.0, 0.0),
+            point(10.0, 0.0),
+            point(10.0, 10.0),
+            point(0.0, 10.0),
+            point(5.0, 5.0),
+        ];
+
+        assert_eq!(
+            delaunay(&points),
+            vec![
+                (0, 1),
+                (0, 3),
+                (0, 4),
+                (1, 2),
+                (1, 4),
+                (2, 3),
+                (2, 4),
+                (3, 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn delaunay_joins_collinear_points_in_a_line() {
+        let points = [
+            point(0.0, 0.0),
+            point(2.0, 0.0),
+            point(1.0, 0.0),
+            point(3.0, 0.0),
+        ];
+
+        assert_eq!(delaunay(&points), vec![(0, 2), (1, 2), (1, 3)]);
+    }
+
+    #[test]
+    fn delaunay_joins_repeated_points_to_each_other() {
+        let points = [
+            point(0.0, 0.0),
+            point(10.0, 0.0),
+            point(0.0, 10.0),
+            point(10.0, 0.0),
+        ];
+
+        // The repeat is only joined to the point it repeats, leaving the triangle as it was
+        assert_eq!(delaunay(&points), vec![(0, 1), (0, 2), (1, 2), (1, 3)]);
+    }
+
+    #[test]
+    fn nearest_matches_brute_force() {
+        let points = scattered(1);
+        let index = SpatialIndex::new(points.clone());
+        let mut rng = StdRng::seed_from_u64(2);
+
+        for _ in 0..100 {
+            let query = point(
+                rng.random_range(-20.0..120.0),
+                rng.random_range(-20.0..70.0),
+            );
+            let mut distances: Vec<_> = points.iter().map(|p| p.dist(&query)).collect();
+            distances.sort_by(f64::total_cmp);
+
+            for k in [1, 3, 10] {
+                let found: Vec<_> = index
+                    .nearest(query, k)
+                    .into_iter()
+                    .map(|i| points[i].dist(&query))
+                    .collect();
+                assert_eq!(found, distances[..k], "{k} nearest to {query:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn within_matches_brute_force() {
+        let points = scattered(3);
+        let index = SpatialIndex::new(points.clone());
+        let mut rng = StdRng::seed_from_u64(4);
+
+        for _ in 0..100 {
+            let query = point(
+                rng.random_range(-20.0..120.0),
+                rng.random_range(-20.0..70.0),
+            );
+            let radius = rng.random_range(0.0..30.0);
+
+            let mut found = index.within(query, radius);
+            found.sort_unstable();
+            let expected: Vec<_> = (0..points.len())
+                .filter(|&i| points[i].dist(&query) <= radius)
+                .collect();
+            assert_eq!(found, expected, "within {radius} of {query:?}");
+        }
+    }
+}
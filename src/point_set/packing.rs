@@ -0,0 +1,304 @@
+//! Sizes for shapes that fill the space around each point without overlapping
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    hash::Hash,
+    rc::Rc,
+};
+
+use bon::bon;
+
+use crate::{
+    geometry::Coordinate,
+    point_set::{PointSet, neighbors::SpatialIndex},
+    shape::Context,
+};
+
+/// How the size of the shape at each point is found
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PackingMode {
+    /// The size is the distance to the nearest neighbor
+    ///
+    /// This is quick, but leaves gaps wherever a point's nearest neighbor is closer to some other
+    /// point.
+    NearestNeighbor,
+
+    /// Circles grow from every point at the same rate, and each stops when it touches another
+    ///
+    /// Circles that stop early leave room for their neighbors to keep growing, so this fills the
+    /// space more tightly than [`PackingMode::NearestNeighbor`].
+    #[default]
+    Grow,
+}
+
+/// The sizes of non-overlapping circles around each point of a [`PointSet`]
+///
+/// Each size is the diameter of a circle, which matches the `size` of shapes such as
+/// [`Polygon`](crate::shape::Polygon):
+///
+/// ```rust,ignore
+/// let packing = Packing::builder().points(&points).gap(4.0).max(200.0).build();
+/// let polygon = Polygon::builder()
+///     .sides(7)
+///     .size_context_fn(packing.size_context_fn())
+///     .color(indigo)
+///     .build();
+/// ```
+///
+/// The sizes are found for the points of the set as they are. When the
+/// [`Canvas`](crate::Canvas) resizes the points to `fill` it, or tiles them for a `repeat`, find
+/// the packing from its [`Canvas::resized_points`](crate::Canvas::resized_points) instead:
+///
+/// ```rust,ignore
+/// let points = canvas.resized_points().unwrap_or_else(|| canvas.points.clone());
+/// let packing = Packing::builder().points(&points).build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Packing<I> {
+    sizes: Rc<HashMap<I, f64>>,
+}
+
+#[bon]
+impl<I: Hash + Eq + Clone + 'static> Packing<I> {
+    /// Find the sizes for the points in `points`
+    ///
+    /// `gap` is the space left between neighboring circles. Sizes are limited to `min..=max`, and
+    /// points without neighbors are given the `max` size, or the `min` size if there is no `max`.
+    /// A `min` larger than the space around a point lets its circle overlap its neighbors.
+    ///
+    /// The `points` must be the points that the shapes are drawn at, which are the
+    /// [`Canvas::resized_points`](crate::Canvas::resized_points) of a canvas that resizes or tiles
+    /// its points. Sizing a shape at an index that isn't in `points` panics.
+    #[builder]
+    pub fn new<P: PointSet<Index = I>>(
+        points: &P,
+        #[builder(default)] mode: PackingMode,
+        #[builder(default = 0.0)] min: f64,
+        #[builder(default = f64::INFINITY)] max: f64,
+        #[builder(default = 0.0)] gap: f64,
+    ) -> Self {
+        let indices: Vec<I> = points.index_iter().collect();
+        let coordinates: Vec<Coordinate> = indices
+            .iter()
+            .map(|index| points.index_to_coordinate(index))
+            .collect();
+
+        let radii = match mode {
+            PackingMode::NearestNeighbor => nearest_radii(&coordinates, gap),
+            PackingMode::Grow => grown_radii(&coordinates, gap, max / 2.0),
+        };
+
+        let sizes = indices
+            .into_iter()
+            .zip(radii)
+            .map(|(index, radius)| {
+                let size = 2.0 * radius;
+                let size = if size.is_finite() {
+                    size.min(max)
+                } else if max.is_finite() {
+                    max
+                } else {
+                    min
+                };
+                (index, size.max(min))
+            })
+            .collect();
+
+        Self {
+            sizes: Rc::new(sizes),
+        }
+    }
+
+    /// The size at `index`, or `None` for an index that is not in the point set
+    pub fn size(&self, index: &I) -> Option<f64> {
+        self.sizes.get(index).copied()
+    }
+
+    /// A function giving the size at each index, for use as the `size_fn` of a shape
+    ///
+    /// The sizes are at the scale of the point set, so they only match the spacing of the points
+    /// on a canvas that keeps the points at their own scale, or scales the shapes along with
+    /// them. [`Packing::size_context_fn`] follows the layout of the canvas instead.
+    ///
+    /// # Panics
+    ///
+    /// The function panics when it is given an index that is not in the point set, such as one
+    /// of a canvas that resizes or tiles the points the packing was found from.
+    pub fn size_fn(&self) -> impl Fn(&I) -> f64 + 'static {
+        let packing = self.clone();
+        move |index| packing.expect_size(index)
+    }
+
+    /// A function giving the size at the point of each context, for use as the
+    /// `size_context_fn` of a shape
+    ///
+    /// The sizes are scaled with the [`Context::layout`] of the points on the canvas, so the
+    /// circles keep to the space around the points wherever the canvas fits and transforms them.
+    /// Where the layout stretches some directions more than others, the sizes are scaled by the
+    /// least stretch.
+    ///
+    /// # Panics
+    ///
+    /// The function panics when it is given a context whose index is not in the point set, such
+    /// as one of a canvas that resizes or tiles the points the packing was found from.
+    pub fn size_context_fn(&self) -> impl Fn(&Context<I>) -> f64 + 'static {
+        let packing = self.clone();
+        move |context| packing.expect_size(&context.index) * context.layout.min_scale()
+    }
+
+    fn expect_size(&self, index: &I) -> f64 {
+        self.size(index).expect(
+            "the index is in the point set of the packing, which must be found from the same \
+             resized or tiled points that the canvas draws",
+        )
+    }
+}
+
+/// Half the distance from each point to its nearest neighbor, less half the gap
+fn nearest_radii(coordinates: &[Coordinate], gap: f64) -> Vec<f64> {
+    let index = SpatialIndex::new(coordinates.to_vec());
+
+    coordinates
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            index
+                .nearest(*point, 2)
+                .into_iter()
+                .find(|j| *j != i)
+                .map_or(f64::INFINITY, |j| {
+                    ((point.dist(&coordinates[j]) - gap) / 2.0).max(0.0)
+                })
+        })
+        .collect()
+}
+
+/// Grow circles from every point at the same rate, stopping each when it touches another or
+/// reaches `max_radius`
+fn grown_radii(coordinates: &[Coordinate], gap: f64, max_radius: f64) -> Vec<f64> {
+    let index = SpatialIndex::new(coordinates.to_vec());
+
+    // While a circle is growing, every stopped circle is at most half way to it, so only points
+    // within twice the nearest neighbor distance can stop it
+    let candidates: Vec<Vec<(usize, f64)>> = coordinates
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            let Some(nearest) = index.nearest(*point, 2).into_iter().find(|j| *j != i) else {
+                return Vec::new();
+            };
+            let reach = 2.0 * point.dist(&coordinates[nearest]);
+
+            index
+                .within(*point, reach)
+                .into_iter()
+                .filter(|j| *j != i)
+                .map(|j| (j, point.dist(&coordinates[j]) - gap))
+                .collect()
+        })
+        .collect();
+
+    let mut radii: Vec<Option<f64>> = vec![None; coordinates.len()];
+    let limit = |i: usize, radii: &[Option<f64>]| {
+        candidates[i]
+            .iter()
+            .map(|(j, space)| match radii[*j] {
+                Some(radius) => space - radius,
+                None => space / 2.0,
+            })
+            .fold(max_radius, f64::min)
+            .max(0.0)
+    };
+
+    // Stopping a circle only leaves more room for the others, so a limit popped from the queue is
+    // final once it is still up to date
+    let mut queue: BinaryHeap<_> = (0..coordinates.len())
+        .map(|i| Reverse((Radius(limit(i, &radii)), i)))
+        .collect();
+    while let Some(Reverse((Radius(radius), i))) = queue.pop() {
+        if radii[i].is_some() {
+            continue;
+        }
+
+        let current = limit(i, &radii);
+        if radius < current {
+            queue.push(Reverse((Radius(current), i)));
+        } else {
+            radii[i] = Some(current);
+        }
+    }
+
+    radii.into_iter().flatten().collect()
+}
+
+/// A radius ordered by [`f64::total_cmp`], for use in a [`BinaryHeap`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Radius(f64);
+
+impl Eq for Radius {}
+
+impl PartialOrd for Radius {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Radius {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{RngExt, SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    fn coordinates(count: usize) -> Vec<Coordinate> {
+        let mut rng = StdRng::seed_from_u64(1);
+        (0..count)
+            .map(|_| Coordinate::Cartesian {
+                x: rng.random_range(0.0..100.0),
+                y: rng.random_range(0.0..100.0),
+            })
+            .collect()
+    }
+
+    fn assert_apart(coordinates: &[Coordinate], radii: &[f64], gap: f64) {
+        assert_eq!(coordinates.len(), radii.len());
+        for i in 0..coordinates.len() {
+            for j in i + 1..coordinates.len() {
+                let space = coordinates[i].dist(&coordinates[j]) - gap;
+                assert!(
+                    radii[i] + radii[j] <= space.max(0.0) + 1e-9,
+                    "circles {i} and {j} overlap"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn grown_circles_do_not_overlap() {
+        let coordinates = coordinates(200);
+        let radii = grown_radii(&coordinates, 2.0, f64::INFINITY);
+        assert_apart(&coordinates, &radii, 2.0);
+    }
+
+    #[test]
+    fn grown_circles_stop_at_the_max() {
+        let coordinates = coordinates(50);
+        let radii = grown_radii(&coordinates, 0.0, 3.0);
+        assert!(radii.iter().all(|radius| *radius <= 3.0));
+        assert_apart(&coordinates, &radii, 0.0);
+    }
+
+    #[test]
+    fn grown_circles_are_no_smaller_than_nearest_neighbor_circles() {
+        let coordinates = coordinates(200);
+        let grown = grown_radii(&coordinates, 1.0, f64::INFINITY);
+        let nearest = nearest_radii(&coordinates, 1.0);
+        assert_apart(&coordinates, &nearest, 1.0);
+        assert!(grown.iter().zip(&nearest).all(|(a, b)| b <= &(a + 1e-9)));
+    }
+}
@@ -1,5 +1,5 @@
 //! Fast poisson disk sampling algorithm for generating blue noise
-use rand::RngExt;
+use rand::{RngExt, SeedableRng, rngs::StdRng};
 use std::{
     collections::HashMap,
     f64::consts::{PI, SQRT_2},
@@ -11,6 +11,7 @@ use itertools::iproduct;
 use crate::{
     geometry::{Angle, BoundingBox, Coordinate},
     point_set::PointSet,
+    tile::Repeat,
 };
 
 /// Index for the grid cell used during the fast poisson disk sampling algorithm
@@ -40,6 +41,17 @@ pub struct PoissonDisk {
     /// The paper suggests `k = 30` as a typical value.
     pub k: u16,
 
+    /// The repeat the sample tiles seamlessly with, if any
+    ///
+    /// Points are sampled on a torus, so the minimum distance holds across the edges of the
+    /// rectangle and into the neighboring tiles.
+    pub repeat: Option<Repeat>,
+
+    /// The seed the points are sampled from
+    ///
+    /// Resized and tiled copies of the sample use the same seed, so they are the same each time.
+    pub seed: u64,
+
     cell_size: f64,
     grid: HashMap<Index, Coordinate>,
 }
@@ -53,11 +65,15 @@ impl PoissonDisk {
         #[builder(with = |x: f64, y: f64| ( x, y ))] size: (f64, f64),
         r: f64,
         k: u16,
+        repeat: Option<Repeat>,
+        #[builder(default = rand::random())] seed: u64,
     ) -> Self {
         let mut poisson_disk = Self {
             size,
             r,
             k,
+            repeat,
+            seed,
             cell_size: r / SQRT_2,
             grid: HashMap::new(),
         };
@@ -67,7 +83,7 @@ impl PoissonDisk {
     }
 
     fn generate(&mut self) {
-        let mut rng = rand::rng();
+        let mut rng = StdRng::seed_from_u64(self.seed);
 
         let init_point = Coordinate::Cartesian {
             x: rng.random_range(0.0..self.size.0),
@@ -82,11 +98,15 @@ impl PoissonDisk {
             let point = actives[i];
 
             if let Some(new_point) = (0..self.k).find_map(|_| {
-                let candidate = point
+                let mut candidate = point
                     + Coordinate::Polar {
                         r: rng.random_range(self.r..self.r * 2.0),
                         phi: Angle::Radian(rng.random_range(0.0..2.0 * PI)),
                     };
+                // Candidates past an edge of a tile continue from the opposite edge
+                if let Some(repeat) = self.repeat {
+                    candidate = repeat.wrap(candidate, self.size);
+                }
 
                 (self.is_in_bounds(&candidate) && self.is_valid(&candidate)).then_some(candidate)
             }) {
@@ -105,6 +125,18 @@ impl PoissonDisk {
     }
 
     fn is_valid(&self, candidate: &Coordinate) -> bool {
+        match self.repeat {
+            // The candidate is checked from every neighboring tile, against the points near
+            // each copy of it
+            Some(repeat) => repeat
+                .neighbors(self.size)
+                .all(|offset| self.is_valid_at(&(*candidate + offset))),
+            None => self.is_valid_at(candidate),
+        }
+    }
+
+    /// Whether `candidate` is far enough from the points in the cells around it
+    fn is_valid_at(&self, candidate: &Coordinate) -> bool {
         let cell_index = candidate.to_cell_index(self.cell_size);
 
         iproduct!(
@@ -122,14 +154,48 @@ impl PoissonDisk {
 impl PointSet for PoissonDisk {
     type Index = Index;
 
+    /// Iterate through the cells row by row, from the top left
     fn index_iter(&self) -> Box<dyn Iterator<Item = Self::Index>> {
-        Box::new(self.grid.keys().copied().collect::<Vec<_>>().into_iter())
+        let mut indices: Vec<Index> = self.grid.keys().copied().collect();
+        indices.sort_by_key(|index| (index.y, index.x));
+
+        Box::new(indices.into_iter())
     }
 
     fn index_to_coordinate(&self, index: &Self::Index) -> Coordinate {
         *self.grid.get(index).unwrap()
     }
 
+    fn count(&self) -> usize {
+        self.grid.len()
+    }
+
+    /// A new sample covering the area, with the same spacing and seed
+    fn resize(&self, width: f64, height: f64) -> Option<Self> {
+        Some(
+            PoissonDisk::builder()
+                .size(width, height)
+                .r(self.r)
+                .k(self.k)
+                .maybe_repeat(self.repeat)
+                .seed(self.seed)
+                .build(),
+        )
+    }
+
+    /// A new sample of the tile, with the same spacing and seed, sampled on a torus
+    fn tile(&self, width: f64, height: f64, repeat: Repeat) -> Option<Self> {
+        Some(
+            PoissonDisk::builder()
+                .size(width, height)
+                .r(self.r)
+                .k(self.k)
+                .repeat(repeat)
+                .seed(self.seed)
+                .build(),
+        )
+    }
+
     fn bounding_box(&self) -> BoundingBox {
         BoundingBox::new(
             Coordinate::Cartesian { x: 0.0, y: 0.0 },
@@ -151,3 +217,29 @@ impl Coordinate {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coordinates(points: &PoissonDisk) -> Vec<(f64, f64)> {
+        points
+            .index_iter()
+            .map(|index| points.index_to_coordinate(&index).to_cartesian())
+            .collect()
+    }
+
+    #[test]
+    fn copies_are_the_same_each_time() {
+        let points = PoissonDisk::builder()
+            .size(100.0, 100.0)
+            .r(10.0)
+            .k(30)
+            .build();
+
+        let tile = |points: &PoissonDisk| points.tile(120.0, 80.0, Repeat::HalfDrop).unwrap();
+        let resize = |points: &PoissonDisk| points.resize(150.0, 90.0).unwrap();
+        assert_eq!(coordinates(&tile(&points)), coordinates(&tile(&points)));
+        assert_eq!(coordinates(&resize(&points)), coordinates(&resize(&points)));
+    }
+}
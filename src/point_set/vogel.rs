@@ -67,6 +67,10 @@ impl PointSet for Vogel {
         Coordinate::Polar { r, phi }
     }
 
+    fn count(&self) -> usize {
+        self.seeds
+    }
+
     fn bounding_box(&self) -> BoundingBox {
         BoundingBox::new(
             Coordinate::Cartesian {
@@ -0,0 +1,239 @@
+//! Settings for printing the [`Canvas`](crate::Canvas) on fabric or paper at a physical size
+use svg::node::element::{Circle, Group, Line, Rectangle};
+
+use crate::geometry::{BoundingBox, Coordinate};
+
+/// The length of crop marks, in millimeters
+const MARK_LENGTH: f64 = 5.0;
+
+/// The gap between the bleed and the marks, in millimeters
+const MARK_OFFSET: f64 = 2.0;
+
+/// The width of the lines of the marks, in millimeters, which is about a quarter of a point
+const MARK_WIDTH: f64 = 0.1;
+
+/// A unit of physical length
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Unit {
+    #[default]
+    Millimeter,
+    Centimeter,
+    Inch,
+}
+
+impl Unit {
+    /// The number of units in an inch
+    fn per_inch(self) -> f64 {
+        match self {
+            Unit::Millimeter => 25.4,
+            Unit::Centimeter => 2.54,
+            Unit::Inch => 1.0,
+        }
+    }
+
+    /// The suffix of the unit in SVG lengths
+    fn suffix(self) -> &'static str {
+        match self {
+            Unit::Millimeter => "mm",
+            Unit::Centimeter => "cm",
+            Unit::Inch => "in",
+        }
+    }
+}
+
+/// How the [`Canvas`](crate::Canvas) is laid out for printing
+///
+/// The size of the canvas is the trimmed size of the print, in pixels at `dpi`. The document is
+/// given a physical `width` and `height` in `unit`, while the `viewBox` stays in pixels, so shapes
+/// are sized the same way as on screen. Use [`Print::to_px`] to size the canvas in `unit`:
+///
+/// ```rust,ignore
+/// let print = Print::builder().dpi(300.0).bleed(3.0).crop_marks(true).build();
+/// let canvas = Canvas::builder()
+///     .size(print.to_px(210.0), print.to_px(297.0))
+///     .print(print)
+///     // ...
+/// ```
+#[derive(Debug, Clone, Copy, bon::Builder)]
+pub struct Print {
+    /// The unit of the physical size, and of the other lengths of the print
+    #[builder(default)]
+    pub unit: Unit,
+
+    /// The number of pixels of the canvas in an inch
+    #[builder(default = 300.0)]
+    pub dpi: f64,
+
+    /// How far the pattern and background extend past the edges of the canvas, to be trimmed off
+    #[builder(default = 0.0)]
+    pub bleed: f64,
+
+    /// The inset from the edges of the canvas of the guide for the safe area
+    ///
+    /// Anything important should be kept inside the guide, so it isn't trimmed off.
+    pub safe_area: Option<f64>,
+
+    /// Whether crop marks are drawn at the corners of the canvas, outside of the bleed
+    #[builder(default)]
+    pub crop_marks: bool,
+
+    /// Whether registration marks are drawn at the middle of each side, outside of the bleed
+    #[builder(default)]
+    pub registration_marks: bool,
+}
+
+impl Print {
+    /// Convert a length in `unit` to pixels of the canvas
+    pub fn to_px(&self, length: f64) -> f64 {
+        length / self.unit.per_inch() * self.dpi
+    }
+
+    /// Convert a length in pixels of the canvas to `unit`
+    pub fn from_px(&self, length: f64) -> f64 {
+        length / self.dpi * self.unit.per_inch()
+    }
+
+    /// A length in pixels of the canvas as an SVG length in `unit`
+    pub(crate) fn to_length(self, length: f64) -> String {
+        let length = (self.from_px(length) * 1000.0).round() / 1000.0;
+        format!("{length}{}", self.unit.suffix())
+    }
+
+    /// Convert a length in millimeters to pixels of the canvas
+    fn mm_to_px(&self, length: f64) -> f64 {
+        length / 25.4 * self.dpi
+    }
+
+    fn has_marks(&self) -> bool {
+        self.crop_marks || self.registration_marks
+    }
+
+    /// The bleed in pixels of the canvas
+    pub(crate) fn bleed_px(&self) -> f64 {
+        self.to_px(self.bleed)
+    }
+
+    /// The space around the canvas in the document, for the bleed and any marks, in pixels
+    pub(crate) fn border_px(&self) -> f64 {
+        let marks = if self.has_marks() {
+            self.mm_to_px(MARK_OFFSET + MARK_LENGTH)
+        } else {
+            0.0
+        };
+
+        self.bleed_px() + marks
+    }
+
+    /// Render the guides and marks for a canvas of `size`
+    pub(crate) fn render_marks(&self, size: (f64, f64)) -> Option<Group> {
+        if self.safe_area.is_none() && !self.has_marks() {
+            return None;
+        }
+
+        let (width, height) = size;
+        let stroke_width = self.mm_to_px(MARK_WIDTH);
+        let start = self.bleed_px() + self.mm_to_px(MARK_OFFSET);
+        let end = start + self.mm_to_px(MARK_LENGTH);
+        let mut group = Group::new().set("id", "print-marks");
+
+        if let Some(inset) = self.safe_area {
+            let inset = self.to_px(inset);
+            group = group.add(
+                Rectangle::new()
+                    .set("x", round(inset))
+                    .set("y", round(inset))
+                    .set("width", round(width - 2.0 * inset))
+                    .set("height", round(height - 2.0 * inset))
+                    .set("fill", "none")
+                    .set("stroke", "cyan")
+                    .set("stroke-width", round(stroke_width))
+                    .set("stroke-dasharray", round(4.0 * stroke_width)),
+            );
+        }
+
+        let line = |from: (f64, f64), to: (f64, f64)| {
+            Line::new()
+                .set("x1", round(from.0))
+                .set("y1", round(from.1))
+                .set("x2", round(to.0))
+                .set("y2", round(to.1))
+        };
+
+        if self.crop_marks {
+            // Each corner has a horizontal and a vertical mark, in line with the edges
+            for (x, y, dx, dy) in [
+                (0.0, 0.0, -1.0, -1.0),
+                (width, 0.0, 1.0, -1.0),
+                (width, height, 1.0, 1.0),
+                (0.0, height, -1.0, 1.0),
+            ] {
+                group = group
+                    .add(line((x + dx * start, y), (x + dx * end, y)))
+                    .add(line((x, y + dy * start), (x, y + dy * end)));
+            }
+        }
+
+        if self.registration_marks {
+            let radius = (end - start) / 4.0;
+            let middle = (start + end) / 2.0;
+            for center in [
+                Coordinate::Cartesian {
+                    x: width / 2.0,
+                    y: -middle,
+                },
+                Coordinate::Cartesian {
+                    x: width + middle,
+                    y: height / 2.0,
+                },
+                Coordinate::Cartesian {
+                    x: width / 2.0,
+                    y: height + middle,
+                },
+                Coordinate::Cartesian {
+                    x: -middle,
+                    y: height / 2.0,
+                },
+            ] {
+                let (x, y) = center.to_cartesian();
+                let half = (end - start) / 2.0;
+                group = group
+                    .add(
+                        Circle::new()
+                            .set("cx", round(x))
+                            .set("cy", round(y))
+                            .set("r", round(radius))
+                            .set("fill", "none"),
+                    )
+                    .add(line((x - half, y), (x + half, y)))
+                    .add(line((x, y - half), (x, y + half)));
+            }
+        }
+
+        // The marks are printed on every plate, so they use the registration color
+        Some(
+            group
+                .set("stroke", "#000")
+                .set("stroke-width", round(stroke_width))
+                .set("fill", "none"),
+        )
+    }
+
+    /// The area that is drawn on, including the bleed, for a canvas of `size`
+    pub(crate) fn bleed_bb(&self, size: (f64, f64)) -> BoundingBox {
+        let bleed = Coordinate::Cartesian {
+            x: self.bleed_px(),
+            y: self.bleed_px(),
+        };
+        BoundingBox::new(
+            Coordinate::origin() - bleed,
+            Coordinate::Cartesian {
+                x: size.0,
+                y: size.1,
+            } + bleed,
+        )
+    }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
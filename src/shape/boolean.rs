@@ -0,0 +1,120 @@
+//! Boolean operations between shapes
+use svg::node::element::Path;
+
+use crate::{
+    geometry::{BoundingBox, Outline},
+    shape::{Context, Shape},
+};
+
+/// A boolean operation between two shapes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// The area covered by either shape
+    Union,
+
+    /// The area covered by the subject, but not the clip
+    Difference,
+
+    /// The area covered by both shapes
+    Intersection,
+
+    /// The area covered by exactly one of the shapes
+    Xor,
+}
+
+/// A shape made by combining the outlines of two shapes with an [`Operation`]
+///
+/// Curves in the outlines are approximated by line segments. The fill and other attributes are
+/// taken from the `subject`.
+pub struct Boolean<I> {
+    /// The operation used to combine the shapes
+    pub operation: Operation,
+
+    /// The shape the operation is applied to
+    pub subject: Box<dyn Shape<Index = I>>,
+
+    /// The shape that is combined with the `subject`
+    pub clip: Box<dyn Shape<Index = I>>,
+}
+
+impl<I> Boolean<I> {
+    /// Define a new boolean shape
+    pub fn new(
+        operation: Operation,
+        subject: impl Shape<Index = I> + 'static,
+        clip: impl Shape<Index = I> + 'static,
+    ) -> Self {
+        Self {
+            operation,
+            subject: Box::new(subject),
+            clip: Box::new(clip),
+        }
+    }
+
+    /// The area covered by either shape
+    pub fn union(
+        subject: impl Shape<Index = I> + 'static,
+        clip: impl Shape<Index = I> + 'static,
+    ) -> Self {
+        Self::new(Operation::Union, subject, clip)
+    }
+
+    /// The area covered by the `subject`, but not the `clip`
+    pub fn difference(
+        subject: impl Shape<Index = I> + 'static,
+        clip: impl Shape<Index = I> + 'static,
+    ) -> Self {
+        Self::new(Operation::Difference, subject, clip)
+    }
+
+    /// The area covered by both shapes
+    pub fn intersection(
+        subject: impl Shape<Index = I> + 'static,
+        clip: impl Shape<Index = I> + 'static,
+    ) -> Self {
+        Self::new(Operation::Intersection, subject, clip)
+    }
+
+    /// The area covered by exactly one of the shapes
+    pub fn xor(
+        subject: impl Shape<Index = I> + 'static,
+        clip: impl Shape<Index = I> + 'static,
+    ) -> Self {
+        Self::new(Operation::Xor, subject, clip)
+    }
+}
+
+impl<I> Shape for Boolean<I> {
+    type Index = I;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let (subject_path, _) = self.subject.generate_path_and_bb(context);
+        let (clip_path, _) = self.clip.generate_path_and_bb(context);
+
+        let subject = Outline::from_path(&subject_path);
+        let clip = Outline::from_path(&clip_path);
+
+        let outline = match self.operation {
+            Operation::Union => subject.union(&clip),
+            Operation::Difference => subject.difference(&clip),
+            Operation::Intersection => subject.intersection(&clip),
+            Operation::Xor => subject.xor(&clip),
+        };
+
+        (
+            subject_path
+                .set("d", outline.to_data())
+                .set("fill-rule", "evenodd"),
+            outline.bounding_box(),
+        )
+    }
+
+    /// The bounds of the subject, along with the clip where it can add to the shape
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        let subject = self.subject.bounds(context)?;
+        match self.operation {
+            Operation::Difference | Operation::Intersection => Some(subject),
+            Operation::Union | Operation::Xor => Some(subject + self.clip.bounds(context)?),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! A shape chosen from several shapes at each point
+use rand::{RngExt, SeedableRng, rngs::StdRng};
+use svg::node::element::{Element, Group, Path};
+
+use crate::{
+    geometry::BoundingBox,
+    noise::{Noise, NoiseField},
+    shape::{Context, ContextFn, Shape, context::mix_seed},
+};
+
+/// Used to derive the seed for random choices from the seed of the context
+const CHOICE_SEED: u64 = 0x6368_6f69_6365;
+
+/// How a [`ShapeChoice`] chooses between its shapes
+pub enum Selector<I> {
+    /// Choose randomly by weight, seeded from the [`Context`]
+    Random,
+
+    /// Choose by a value from `0.0` to `1.0`, such as a sample of a [`NoiseField`]
+    ///
+    /// The range is split between the shapes by weight, so nearby points with similar values get
+    /// the same shape.
+    Value(ContextFn<I, f64>),
+
+    /// Choose the shape at the returned position, wrapping around the list of shapes
+    Position(ContextFn<I, usize>),
+}
+
+/// A shape that draws one of several shapes at each point, such as a circle, star or polygon
+///
+/// By default the shape is chosen randomly by weight. As the randomness is seeded from the
+/// [`Context`], the choice is stable for a given point and [`Canvas`](crate::Canvas) seed.
+pub struct ShapeChoice<I> {
+    /// The list of [`Shape`] to choose from
+    pub shapes: Vec<Box<dyn Shape<Index = I>>>,
+
+    /// The weight of each shape, in the same order as `shapes`
+    pub weights: Vec<f64>,
+
+    /// How the shape is chosen
+    pub selector: Selector<I>,
+}
+
+impl<I> ShapeChoice<I> {
+    /// Define a new choice with no shapes, chosen randomly
+    pub fn new() -> Self {
+        Self {
+            shapes: Vec::new(),
+            weights: Vec::new(),
+            selector: Selector::Random,
+        }
+    }
+
+    /// Add a shape to choose from
+    ///
+    /// Shapes with a larger `weight` are chosen more often, unless a function of `Index` chooses
+    /// the shape.
+    pub fn add_shape(&mut self, shape: impl Shape<Index = I> + 'static, weight: f64) {
+        self.shapes.push(Box::new(shape));
+        self.weights.push(weight.max(0.0));
+    }
+
+    /// Choose the shape at the position returned by `func`
+    pub fn select_fn(&mut self, func: impl Fn(&I) -> usize + 'static) {
+        self.select_context_fn(move |context| func(&context.index));
+    }
+
+    /// Choose the shape at the position returned by `func`
+    pub fn select_context_fn(&mut self, func: impl Fn(&Context<I>) -> usize + 'static) {
+        self.selector = Selector::Position(Box::new(func));
+    }
+
+    /// Choose the shape by weight using a value from `0.0` to `1.0` returned by `func`
+    pub fn select_value_context_fn(&mut self, func: impl Fn(&Context<I>) -> f64 + 'static) {
+        self.selector = Selector::Value(Box::new(func));
+    }
+
+    /// Choose the shape by weight using a [`NoiseField`], so that shapes form patches
+    pub fn select_noise<N: Noise + 'static>(&mut self, field: NoiseField<N>) {
+        self.select_value_context_fn(move |context| field.sample(context));
+    }
+
+    /// Choose a shape for the point in `context`
+    fn choose(&self, context: &Context<I>) -> Option<&dyn Shape<Index = I>> {
+        let position = match &self.selector {
+            Selector::Random => {
+                let mut rng = StdRng::seed_from_u64(mix_seed(context.seed, CHOICE_SEED));
+                self.weighted_position(rng.random())
+            }
+            Selector::Value(func) => self.weighted_position(func(context)),
+            Selector::Position(func) => Some(func(context) % self.shapes.len().max(1)),
+        };
+
+        position
+            .and_then(|position| self.shapes.get(position))
+            .map(|shape| shape.as_ref())
+    }
+
+    /// Find the shape whose share of the cumulative weights contains `t`
+    fn weighted_position(&self, t: f64) -> Option<usize> {
+        let total: f64 = self.weights.iter().sum();
+        if !(total > 0.0 && total.is_finite()) {
+            return None;
+        }
+
+        let target = t.clamp(0.0, 1.0) * total;
+        let mut cumulative = 0.0;
+
+        self.weights
+            .iter()
+            .position(|weight| {
+                cumulative += weight;
+                target < cumulative
+            })
+            .or_else(|| self.weights.iter().rposition(|weight| *weight > 0.0))
+    }
+}
+
+impl<I> Default for ShapeChoice<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> Shape for ShapeChoice<I> {
+    type Index = I;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        self.choose(context)
+            .map_or((Path::new(), BoundingBox::empty()), |shape| {
+                shape.generate_path_and_bb(context)
+            })
+    }
+
+    fn generate_element_and_bb(&self, context: &Context<Self::Index>) -> (Element, BoundingBox) {
+        self.choose(context)
+            .map_or((Group::new().into(), BoundingBox::empty()), |shape| {
+                shape.generate_element_and_bb(context)
+            })
+    }
+
+    fn generate_path_element_and_bb(
+        &self,
+        context: &Context<Self::Index>,
+    ) -> (Path, Element, BoundingBox) {
+        self.choose(context).map_or(
+            (Path::new(), Group::new().into(), BoundingBox::empty()),
+            |shape| shape.generate_path_element_and_bb(context),
+        )
+    }
+
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        self.choose(context)
+            .map_or(Some(BoundingBox::empty()), |shape| shape.bounds(context))
+    }
+}
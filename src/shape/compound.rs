@@ -0,0 +1,72 @@
+//! A shape made up of several shapes, with holes where they overlap
+use svg::node::element::Path;
+
+use crate::{
+    geometry::BoundingBox,
+    shape::{Context, Shape},
+};
+
+/// A shape made up of the outlines of several shapes, filled using the even-odd rule
+///
+/// Areas covered by an odd number of shapes are filled, so a shape drawn inside another cuts a
+/// true hole in it. The fill and other attributes are taken from the first shape.
+pub struct Compound<I> {
+    /// The list of [`Shape`] whose outlines are combined
+    pub shapes: Vec<Box<dyn Shape<Index = I>>>,
+}
+
+impl<I> Compound<I> {
+    /// Define a new compound shape with no shapes
+    pub fn new() -> Self {
+        Self { shapes: Vec::new() }
+    }
+
+    /// Add a shape to the compound shape
+    pub fn add_shape(&mut self, shape: impl Shape<Index = I> + 'static) {
+        self.shapes.push(Box::new(shape));
+    }
+}
+
+impl<I> Default for Compound<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> Shape for Compound<I> {
+    type Index = I;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let mut paths_and_bbs = self
+            .shapes
+            .iter()
+            .map(|shape| shape.generate_path_and_bb(context));
+
+        let Some((first_path, first_bb)) = paths_and_bbs.next() else {
+            return (Path::new(), BoundingBox::empty());
+        };
+
+        let (data, bb) = paths_and_bbs.fold(
+            (path_data(&first_path), first_bb),
+            |(data, bb), (path, path_bb)| (format!("{data} {}", path_data(&path)), bb + path_bb),
+        );
+
+        (first_path.set("d", data).set("fill-rule", "evenodd"), bb)
+    }
+
+    /// The bounds of every shape together, if they are all known
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        self.shapes
+            .iter()
+            .try_fold(BoundingBox::empty(), |bb, shape| {
+                Some(bb + shape.bounds(context)?)
+            })
+    }
+}
+
+fn path_data(path: &Path) -> String {
+    path.get_attributes()
+        .get("d")
+        .map(|d| d.to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,128 @@
+//! Lines joining neighboring points
+use std::{collections::HashMap, hash::Hash};
+
+use svg::node::element::{Path, path::Data};
+
+use crate::{
+    Color,
+    geometry::{BoundingBox, Coordinate},
+    point_set::neighbors::Edges,
+    shape::{Context, ContextFn, Shape},
+};
+use connector_builder::{IsUnset, SetBendFn, SetColorFn, SetWidthFn, State};
+
+/// Lines joining each point to its neighbors, such as the threads of a kumo (spider web) pattern
+///
+/// The edges are found with [`PointSet::edges`](crate::point_set::PointSet::edges). Each edge is
+/// drawn once, by the shape at its `from` point, and follows the layout of the points on the
+/// [`Canvas`](crate::Canvas) through [`Context::layout`].
+///
+/// The edges must be found from the points the canvas draws. When the canvas resizes the points
+/// to `fill` it, or tiles them for a `repeat`, find the edges from its
+/// [`Canvas::resized_points`](crate::Canvas::resized_points). Edges of a tile aren't found across
+/// its edges, so the lines don't continue into the neighboring tiles.
+///
+/// ```rust,ignore
+/// let connector = Connector::builder()
+///     .edges(points.edges(Neighborhood::Delaunay))
+///     .width(2.0)
+///     .color(indigo)
+///     .build();
+/// ```
+#[derive(bon::Builder)]
+pub struct Connector<I: Hash + Eq> {
+    /// The vectors to the neighbors drawn from each point
+    #[builder(with = |edges: Edges<I>| {
+        let mut vectors = HashMap::<I, Vec<Coordinate>>::new();
+        for edge in edges.edges {
+            vectors.entry(edge.from).or_default().push(edge.vector);
+        }
+        vectors
+    })]
+    pub edges: HashMap<I, Vec<Coordinate>>,
+
+    /// The width of the lines
+    #[builder(
+        setters(name = width_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub width_fn: ContextFn<I, f64>,
+
+    /// How far the lines bend to the side, relative to their length
+    ///
+    /// `0.0` draws straight lines, and positive values bend to the right.
+    #[builder(
+        default = Box::new(|_| 0.0),
+        setters(name = bend_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub bend_fn: ContextFn<I, f64>,
+
+    /// The color of the lines
+    #[builder(
+        setters(name = color_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Color + 'static| Box::new(func)
+    )]
+    pub color_fn: ContextFn<I, Color>,
+}
+
+impl<I: Hash + Eq> Shape for Connector<I> {
+    type Index = I;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let Some(vectors) = self.edges.get(&context.index) else {
+            return (Path::new(), BoundingBox::empty());
+        };
+
+        let width = (self.width_fn)(context);
+        let bend = (self.bend_fn)(context);
+        let color = (self.color_fn)(context);
+
+        let mut points = vec![Coordinate::origin()];
+        let data = vectors.iter().fold(Data::new(), |data, vector| {
+            let vector = context.layout.apply(*vector);
+            let data = data.move_to((0, 0));
+            let end = vector.to_rounded_cartesian(3);
+            points.push(vector);
+
+            if bend == 0.0 {
+                data.line_to(end)
+            } else {
+                // The control point is to the right of the middle of the line
+                let (x, y) = vector.to_cartesian();
+                let control = vector / 2.0 + Coordinate::Cartesian { x: -y, y: x } * bend;
+                points.push(control);
+
+                let (control_x, control_y) = control.to_rounded_cartesian(3);
+                data.quadratic_curve_to((control_x, control_y, end.0, end.1))
+            }
+        });
+
+        let bb = BoundingBox::from_points(&points);
+        let margin = Coordinate::Cartesian {
+            x: width / 2.0,
+            y: width / 2.0,
+        };
+
+        (
+            Path::new()
+                .set("d", data)
+                .set("fill", "none")
+                .set("stroke", color.to_svg_color())
+                .set("stroke-opacity", color.to_opacity_percent())
+                .set("stroke-width", width)
+                .set("stroke-linecap", "round"),
+            BoundingBox::new(bb.min() - margin, bb.max() + margin),
+        )
+    }
+}
+
+impl<I: Hash + Eq, S: State> ConnectorBuilder<I, S> {
+    impl_index_setter!(ConnectorBuilder, width_fn -> width_context_fn: f64, SetWidthFn, WidthFn);
+    impl_index_setter!(ConnectorBuilder, bend_fn -> bend_context_fn: f64, SetBendFn, BendFn);
+    impl_index_setter!(ConnectorBuilder, color_fn -> color_context_fn: Color, SetColorFn, ColorFn);
+
+    impl_static_setter!(ConnectorBuilder, width -> width_fn: f64, SetWidthFn, WidthFn);
+    impl_static_setter!(ConnectorBuilder, bend -> bend_fn: f64, SetBendFn, BendFn);
+    impl_static_setter!(ConnectorBuilder, color -> color_fn: Color, SetColorFn, ColorFn);
+}
@@ -0,0 +1,114 @@
+//! Information about where a shape is being drawn
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::{
+    geometry::{Angle, Coordinate},
+    layout::Transform,
+};
+
+/// The context in which a [`Shape`](crate::shape::Shape) is drawn
+///
+/// Along with the `Index` of the point, this describes where the point is on the
+/// [`Canvas`](crate::Canvas). This allows shapes to vary depending on their position on the canvas,
+/// regardless of the [`PointSet`](crate::point_set::PointSet) used.
+#[derive(Debug, Clone, Copy)]
+pub struct Context<I> {
+    /// The `Index` of the point in the `PointSet`
+    pub index: I,
+
+    /// The coordinate of the point on the canvas
+    pub coordinate: Coordinate,
+
+    /// The size of the canvas
+    pub canvas_size: (f64, f64),
+
+    /// The number of points in the `PointSet`
+    pub count: usize,
+
+    /// The seed used for any randomness in the shape
+    ///
+    /// This is derived from the seed of the `Canvas`, and differs for each point and layer. Shapes
+    /// drawn with the same seed, such as the children of a
+    /// [`ShapeStack`](crate::shape::ShapeStack), make the same random choices.
+    pub seed: u64,
+
+    /// How the `PointSet` is laid out on the canvas, relative to the scale of the shape
+    ///
+    /// Applying this to a vector between points of the `PointSet`, such as an
+    /// [`Edge`](crate::point_set::neighbors::Edge), gives the vector between the points as they
+    /// are drawn, in the coordinates of the shape. It includes the `fit` and `transform` of the
+    /// [`Canvas`](crate::Canvas), and is the identity for a new context.
+    pub layout: Transform,
+}
+
+impl<I> Context<I> {
+    /// Define a new context
+    pub fn new(
+        index: I,
+        coordinate: Coordinate,
+        canvas_size: (f64, f64),
+        count: usize,
+        seed: u64,
+    ) -> Self {
+        Self {
+            index,
+            coordinate,
+            canvas_size,
+            count,
+            seed,
+            layout: Transform::new(),
+        }
+    }
+
+    /// A random number generator seeded from the `seed` of the context
+    pub fn rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed)
+    }
+
+    /// The position of the point on the canvas, normalized to `0.0..=1.0`
+    ///
+    /// `(0.0, 0.0)` is the top left of the canvas, and `(1.0, 1.0)` is the bottom right.
+    pub fn position(&self) -> (f64, f64) {
+        let (x, y) = self.coordinate.to_cartesian();
+        (x / self.canvas_size.0, y / self.canvas_size.1)
+    }
+
+    /// The center of the canvas
+    pub fn center(&self) -> Coordinate {
+        Coordinate::Cartesian {
+            x: self.canvas_size.0 / 2.0,
+            y: self.canvas_size.1 / 2.0,
+        }
+    }
+
+    /// The distance of the point from the center of the canvas
+    pub fn distance(&self) -> f64 {
+        self.coordinate.dist(&self.center())
+    }
+
+    /// The distance of the point from the center of the canvas, normalized so that the corners of
+    /// the canvas are `1.0`
+    pub fn normalized_distance(&self) -> f64 {
+        self.distance() / self.center().dist(&Coordinate::origin())
+    }
+
+    /// The angle of the point from the center of the canvas
+    ///
+    /// The angle is measured clockwise from the positive x-axis, as in SVG.
+    pub fn angle(&self) -> Angle {
+        (self.coordinate - self.center()).to_polar().1
+    }
+}
+
+/// Combine a seed with a value to derive a new seed
+///
+/// This uses the SplitMix64 finalizer, so that similar inputs give unrelated seeds.
+pub(crate) fn mix_seed(seed: u64, value: u64) -> u64 {
+    let mut z = seed
+        ^ value
+            .wrapping_add(0x9e37_79b9_7f4a_7c15)
+            .wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
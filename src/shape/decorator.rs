@@ -0,0 +1,655 @@
+//! Wrappers that change how any shape is placed or drawn
+use std::f64::consts::PI;
+
+use rand::{RngExt, SeedableRng, rngs::StdRng};
+use svg::node::element::{Element, Group, Path};
+
+use crate::{
+    effect::{BlendMode, Effect},
+    geometry::{Angle, BezierPath, BoundingBox, Coordinate},
+    shape::{Context, ContextFn, Shape, context::mix_seed},
+};
+
+/// Used to derive the seed for [`ShapeExt::position_jitter`] from the seed of the context
+const JITTER_SEED: u64 = 0x6a69_7474_6572;
+
+/// Extension methods for wrapping any [`Shape`]
+///
+/// Each method returns a new `Shape` that wraps the original one. When drawn on the
+/// [`Canvas`](crate::Canvas) the change is applied as an SVG attribute on a group, and the
+/// `BoundingBox` is adjusted to match. Like the setters of the shapes themselves, the methods
+/// ending in `_fn` take a function of `Index`, and those ending in `_context_fn` take a function
+/// of the [`Context`].
+pub trait ShapeExt: Shape + Sized {
+    /// Move the shape away from its point
+    fn offset_fn(self, func: impl Fn(&Self::Index) -> Coordinate + 'static) -> Offset<Self> {
+        self.offset_context_fn(move |context| func(&context.index))
+    }
+
+    /// Move the shape away from its point
+    fn offset_context_fn(
+        self,
+        func: impl Fn(&Context<Self::Index>) -> Coordinate + 'static,
+    ) -> Offset<Self> {
+        Offset {
+            shape: self,
+            offset_fn: Box::new(func),
+        }
+    }
+
+    /// Move the shape by a random distance of up to `max_distance` from its point
+    ///
+    /// The offset is uniformly distributed within a circle, and seeded from the [`Context`].
+    fn position_jitter(self, max_distance: f64) -> Offset<Self> {
+        self.offset_context_fn(move |context| {
+            let mut rng = StdRng::seed_from_u64(mix_seed(context.seed, JITTER_SEED));
+
+            Coordinate::Polar {
+                r: max_distance * rng.random::<f64>().sqrt(),
+                phi: Angle::Radian(rng.random::<f64>() * 2.0 * PI),
+            }
+        })
+    }
+
+    /// Scale the shape around its point
+    fn scale_fn(self, func: impl Fn(&Self::Index) -> f64 + 'static) -> Scale<Self> {
+        self.scale_context_fn(move |context| func(&context.index))
+    }
+
+    /// Scale the shape around its point
+    fn scale_context_fn(
+        self,
+        func: impl Fn(&Context<Self::Index>) -> f64 + 'static,
+    ) -> Scale<Self> {
+        Scale {
+            shape: self,
+            scale_fn: Box::new(func),
+        }
+    }
+
+    /// Rotate the shape around its point
+    fn rotate_fn(self, func: impl Fn(&Self::Index) -> Angle + 'static) -> Rotate<Self> {
+        self.rotate_context_fn(move |context| func(&context.index))
+    }
+
+    /// Rotate the shape around its point
+    fn rotate_context_fn(
+        self,
+        func: impl Fn(&Context<Self::Index>) -> Angle + 'static,
+    ) -> Rotate<Self> {
+        Rotate {
+            shape: self,
+            rotation_fn: Box::new(func),
+        }
+    }
+
+    /// Only draw the shape where `func` returns `true`
+    fn visible_fn(self, func: impl Fn(&Self::Index) -> bool + 'static) -> Visible<Self> {
+        self.visible_context_fn(move |context| func(&context.index))
+    }
+
+    /// Only draw the shape where `func` returns `true`
+    fn visible_context_fn(
+        self,
+        func: impl Fn(&Context<Self::Index>) -> bool + 'static,
+    ) -> Visible<Self> {
+        Visible {
+            shape: self,
+            visible_fn: Box::new(func),
+        }
+    }
+
+    /// Apply an [`Effect`] to the shape
+    fn effect(self, effect: Effect) -> Filtered<Self> {
+        self.effect_context_fn(move |_| effect)
+    }
+
+    /// Apply an [`Effect`] to the shape, with parameters that can vary between points
+    fn effect_fn(self, func: impl Fn(&Self::Index) -> Effect + 'static) -> Filtered<Self> {
+        self.effect_context_fn(move |context| func(&context.index))
+    }
+
+    /// Apply an [`Effect`] to the shape, with parameters that can vary between points
+    fn effect_context_fn(
+        self,
+        func: impl Fn(&Context<Self::Index>) -> Effect + 'static,
+    ) -> Filtered<Self> {
+        Filtered {
+            shape: self,
+            effect_fn: Box::new(func),
+        }
+    }
+
+    /// Set the opacity of the whole shape, from `0.0` to `1.0`
+    fn opacity_fn(self, func: impl Fn(&Self::Index) -> f64 + 'static) -> Opacity<Self> {
+        self.opacity_context_fn(move |context| func(&context.index))
+    }
+
+    /// Set the opacity of the whole shape, from `0.0` to `1.0`
+    fn opacity_context_fn(
+        self,
+        func: impl Fn(&Context<Self::Index>) -> f64 + 'static,
+    ) -> Opacity<Self> {
+        Opacity {
+            shape: self,
+            opacity_fn: Box::new(func),
+        }
+    }
+
+    /// Blend the shape with what is drawn below it
+    fn blend_mode(self, mode: BlendMode) -> Blended<Self> {
+        self.blend_mode_context_fn(move |_| mode)
+    }
+
+    /// Blend the shape with what is drawn below it
+    fn blend_mode_fn(self, func: impl Fn(&Self::Index) -> BlendMode + 'static) -> Blended<Self> {
+        self.blend_mode_context_fn(move |context| func(&context.index))
+    }
+
+    /// Blend the shape with what is drawn below it
+    fn blend_mode_context_fn(
+        self,
+        func: impl Fn(&Context<Self::Index>) -> BlendMode + 'static,
+    ) -> Blended<Self> {
+        Blended {
+            shape: self,
+            blend_mode_fn: Box::new(func),
+        }
+    }
+
+    /// Isolate the shape, so that blend modes within it only mix with each other
+    ///
+    /// This is useful for a [`ShapeStack`](crate::shape::ShapeStack) whose layers blend. A shape
+    /// merged into a single path, such as in a [`Compound`](crate::shape::Compound), has nothing
+    /// within it to isolate, so the path is unchanged.
+    fn isolate(self) -> Isolated<Self> {
+        Isolated { shape: self }
+    }
+}
+
+impl<S: Shape> ShapeExt for S {}
+
+/// A shape moved away from its point, created with [`ShapeExt::offset_fn`]
+pub struct Offset<S: Shape> {
+    pub shape: S,
+    pub offset_fn: ContextFn<S::Index, Coordinate>,
+}
+
+impl<S: Shape> Shape for Offset<S> {
+    type Index = S::Index;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let offset = (self.offset_fn)(context);
+        transform_path(self.shape.generate_path_and_bb(context), |c| c + offset)
+    }
+
+    fn generate_element_and_bb(&self, context: &Context<Self::Index>) -> (Element, BoundingBox) {
+        let offset = (self.offset_fn)(context);
+        let (x, y) = offset.to_rounded_cartesian(3);
+        let (element, bb) = self.shape.generate_element_and_bb(context);
+
+        (
+            wrap(element, "transform", format!("translate({x},{y})")),
+            bb + offset,
+        )
+    }
+
+    fn generate_path_element_and_bb(
+        &self,
+        context: &Context<Self::Index>,
+    ) -> (Path, Element, BoundingBox) {
+        let offset = (self.offset_fn)(context);
+        let (x, y) = offset.to_rounded_cartesian(3);
+        let (path, element, bb) = self.shape.generate_path_element_and_bb(context);
+
+        (
+            map_path(path, |c| c + offset),
+            wrap(element, "transform", format!("translate({x},{y})")),
+            bb + offset,
+        )
+    }
+
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        let offset = (self.offset_fn)(context);
+        self.shape.bounds(context).map(|bb| bb + offset)
+    }
+}
+
+/// A shape scaled around its point, created with [`ShapeExt::scale_fn`]
+pub struct Scale<S: Shape> {
+    pub shape: S,
+    pub scale_fn: ContextFn<S::Index, f64>,
+}
+
+impl<S: Shape> Shape for Scale<S> {
+    type Index = S::Index;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let scale = (self.scale_fn)(context);
+        transform_path(self.shape.generate_path_and_bb(context), |c| c * scale)
+    }
+
+    fn generate_element_and_bb(&self, context: &Context<Self::Index>) -> (Element, BoundingBox) {
+        let scale = (self.scale_fn)(context);
+        let (element, bb) = self.shape.generate_element_and_bb(context);
+
+        (
+            wrap(element, "transform", format!("scale({scale:.3})")),
+            bb.map(|c| c * scale),
+        )
+    }
+
+    fn generate_path_element_and_bb(
+        &self,
+        context: &Context<Self::Index>,
+    ) -> (Path, Element, BoundingBox) {
+        let scale = (self.scale_fn)(context);
+        let (path, element, bb) = self.shape.generate_path_element_and_bb(context);
+
+        (
+            map_path(path, |c| c * scale),
+            wrap(element, "transform", format!("scale({scale:.3})")),
+            bb.map(|c| c * scale),
+        )
+    }
+
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        let scale = (self.scale_fn)(context);
+        self.shape.bounds(context).map(|bb| bb.map(|c| c * scale))
+    }
+}
+
+/// A shape rotated around its point, created with [`ShapeExt::rotate_fn`]
+pub struct Rotate<S: Shape> {
+    pub shape: S,
+    pub rotation_fn: ContextFn<S::Index, Angle>,
+}
+
+impl<S: Shape> Shape for Rotate<S> {
+    type Index = S::Index;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let rotation = (self.rotation_fn)(context);
+        transform_path(self.shape.generate_path_and_bb(context), |c| {
+            c.rotate(rotation)
+        })
+    }
+
+    fn generate_element_and_bb(&self, context: &Context<Self::Index>) -> (Element, BoundingBox) {
+        let rotation = (self.rotation_fn)(context);
+        let (element, bb) = self.shape.generate_element_and_bb(context);
+
+        (
+            wrap(
+                element,
+                "transform",
+                format!("rotate({:.3})", rotation.to_degree()),
+            ),
+            bb.map(|c| c.rotate(rotation)),
+        )
+    }
+
+    fn generate_path_element_and_bb(
+        &self,
+        context: &Context<Self::Index>,
+    ) -> (Path, Element, BoundingBox) {
+        let rotation = (self.rotation_fn)(context);
+        let (path, element, bb) = self.shape.generate_path_element_and_bb(context);
+
+        (
+            map_path(path, |c| c.rotate(rotation)),
+            wrap(
+                element,
+                "transform",
+                format!("rotate({:.3})", rotation.to_degree()),
+            ),
+            bb.map(|c| c.rotate(rotation)),
+        )
+    }
+
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        let rotation = (self.rotation_fn)(context);
+        self.shape
+            .bounds(context)
+            .map(|bb| bb.map(|c| c.rotate(rotation)))
+    }
+}
+
+/// A shape that is only drawn at some points, created with [`ShapeExt::visible_fn`]
+pub struct Visible<S: Shape> {
+    pub shape: S,
+    pub visible_fn: ContextFn<S::Index, bool>,
+}
+
+impl<S: Shape> Shape for Visible<S> {
+    type Index = S::Index;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        if (self.visible_fn)(context) {
+            self.shape.generate_path_and_bb(context)
+        } else {
+            (Path::new(), BoundingBox::empty())
+        }
+    }
+
+    fn generate_element_and_bb(&self, context: &Context<Self::Index>) -> (Element, BoundingBox) {
+        if (self.visible_fn)(context) {
+            self.shape.generate_element_and_bb(context)
+        } else {
+            (Group::new().into(), BoundingBox::empty())
+        }
+    }
+
+    fn generate_path_element_and_bb(
+        &self,
+        context: &Context<Self::Index>,
+    ) -> (Path, Element, BoundingBox) {
+        if (self.visible_fn)(context) {
+            self.shape.generate_path_element_and_bb(context)
+        } else {
+            (Path::new(), Group::new().into(), BoundingBox::empty())
+        }
+    }
+
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        if (self.visible_fn)(context) {
+            self.shape.bounds(context)
+        } else {
+            Some(BoundingBox::empty())
+        }
+    }
+}
+
+/// A shape drawn with an opacity, created with [`ShapeExt::opacity_fn`]
+pub struct Opacity<S: Shape> {
+    pub shape: S,
+    pub opacity_fn: ContextFn<S::Index, f64>,
+}
+
+impl<S: Shape> Shape for Opacity<S> {
+    type Index = S::Index;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let opacity = (self.opacity_fn)(context).clamp(0.0, 1.0);
+        let (path, bb) = self.shape.generate_path_and_bb(context);
+
+        (multiply_opacity(path, opacity), bb)
+    }
+
+    fn generate_element_and_bb(&self, context: &Context<Self::Index>) -> (Element, BoundingBox) {
+        let opacity = (self.opacity_fn)(context).clamp(0.0, 1.0);
+        let (element, bb) = self.shape.generate_element_and_bb(context);
+
+        (wrap(element, "opacity", format!("{opacity:.3}")), bb)
+    }
+
+    fn generate_path_element_and_bb(
+        &self,
+        context: &Context<Self::Index>,
+    ) -> (Path, Element, BoundingBox) {
+        let opacity = (self.opacity_fn)(context).clamp(0.0, 1.0);
+        let (path, element, bb) = self.shape.generate_path_element_and_bb(context);
+
+        (
+            multiply_opacity(path, opacity),
+            wrap(element, "opacity", format!("{opacity:.3}")),
+            bb,
+        )
+    }
+
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        self.shape.bounds(context)
+    }
+}
+
+/// A shape blended with what is drawn below it, created with [`ShapeExt::blend_mode`]
+pub struct Blended<S: Shape> {
+    pub shape: S,
+    pub blend_mode_fn: ContextFn<S::Index, BlendMode>,
+}
+
+impl<S: Shape> Shape for Blended<S> {
+    type Index = S::Index;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let mode = (self.blend_mode_fn)(context);
+        let (path, bb) = self.shape.generate_path_and_bb(context);
+
+        (set_style(path, "mix-blend-mode", mode.to_css()), bb)
+    }
+
+    fn generate_element_and_bb(&self, context: &Context<Self::Index>) -> (Element, BoundingBox) {
+        let mode = (self.blend_mode_fn)(context);
+        let (element, bb) = self.shape.generate_element_and_bb(context);
+
+        (
+            wrap(
+                element,
+                "style",
+                format!("mix-blend-mode:{}", mode.to_css()),
+            ),
+            bb,
+        )
+    }
+
+    fn generate_path_element_and_bb(
+        &self,
+        context: &Context<Self::Index>,
+    ) -> (Path, Element, BoundingBox) {
+        let mode = (self.blend_mode_fn)(context);
+        let (path, element, bb) = self.shape.generate_path_element_and_bb(context);
+
+        (
+            set_style(path, "mix-blend-mode", mode.to_css()),
+            wrap(
+                element,
+                "style",
+                format!("mix-blend-mode:{}", mode.to_css()),
+            ),
+            bb,
+        )
+    }
+
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        self.shape.bounds(context)
+    }
+}
+
+/// A shape isolated from what is drawn below it, created with [`ShapeExt::isolate`]
+pub struct Isolated<S: Shape> {
+    pub shape: S,
+}
+
+impl<S: Shape> Shape for Isolated<S> {
+    type Index = S::Index;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        self.shape.generate_path_and_bb(context)
+    }
+
+    fn generate_element_and_bb(&self, context: &Context<Self::Index>) -> (Element, BoundingBox) {
+        let (element, bb) = self.shape.generate_element_and_bb(context);
+        (wrap(element, "style", "isolation:isolate".to_string()), bb)
+    }
+
+    fn generate_path_element_and_bb(
+        &self,
+        context: &Context<Self::Index>,
+    ) -> (Path, Element, BoundingBox) {
+        let (path, element, bb) = self.shape.generate_path_element_and_bb(context);
+        (
+            path,
+            wrap(element, "style", "isolation:isolate".to_string()),
+            bb,
+        )
+    }
+
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        self.shape.bounds(context)
+    }
+}
+
+/// A shape drawn with an [`Effect`], created with [`ShapeExt::effect`]
+///
+/// The effect is only drawn on the [`Canvas`](crate::Canvas). When the shape is part of a
+/// [`Compound`](crate::shape::Compound) or [`Boolean`](crate::shape::Boolean) shape, only its
+/// outline is used.
+pub struct Filtered<S: Shape> {
+    pub shape: S,
+    pub effect_fn: ContextFn<S::Index, Effect>,
+}
+
+impl<S: Shape> Shape for Filtered<S> {
+    type Index = S::Index;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        self.shape.generate_path_and_bb(context)
+    }
+
+    fn generate_element_and_bb(&self, context: &Context<Self::Index>) -> (Element, BoundingBox) {
+        let effect = (self.effect_fn)(context);
+        let (element, bb) = self.shape.generate_element_and_bb(context);
+
+        if bb.is_empty() {
+            return (element, bb);
+        }
+
+        (effect.apply(element, bb).into(), effect.expand_bb(bb))
+    }
+
+    fn generate_path_element_and_bb(
+        &self,
+        context: &Context<Self::Index>,
+    ) -> (Path, Element, BoundingBox) {
+        let effect = (self.effect_fn)(context);
+        let (path, element, bb) = self.shape.generate_path_element_and_bb(context);
+
+        if bb.is_empty() {
+            return (path, element, bb);
+        }
+
+        (path, effect.apply(element, bb).into(), effect.expand_bb(bb))
+    }
+
+    /// The bounds of the shape, grown to fit the effect
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        let bb = self.shape.bounds(context)?;
+        if bb.is_empty() {
+            return Some(bb);
+        }
+
+        Some((self.effect_fn)(context).expand_bb(bb))
+    }
+}
+
+/// Wrap `element` in a group with a single attribute
+fn wrap(element: Element, name: &str, value: String) -> Element {
+    Group::new().set(name, value).add(element).into()
+}
+
+/// Multiply the `opacity` of a path by `opacity`, as nested groups with opacities would
+fn multiply_opacity(path: Path, opacity: f64) -> Path {
+    let current = path
+        .get_attributes()
+        .get("opacity")
+        .and_then(|value| value.parse::<f64>().ok())
+        .unwrap_or(1.0);
+    path.set("opacity", format!("{:.3}", current * opacity))
+}
+
+/// Set a property in the `style` of a path, replacing any declaration of it that the path already
+/// has and keeping the others
+fn set_style(path: Path, property: &str, value: &str) -> Path {
+    let declaration = format!("{property}:{value}");
+    let style = match path.get_attributes().get("style") {
+        Some(style) => style
+            .split(';')
+            .filter(|existing| {
+                !existing.trim().is_empty()
+                    && existing.split(':').next().map(str::trim) != Some(property)
+            })
+            .chain([declaration.as_str()])
+            .collect::<Vec<_>>()
+            .join(";"),
+        None => declaration,
+    };
+    path.set("style", style)
+}
+
+/// Apply `func` to the outline and bounding box of a path
+///
+/// This keeps the geometry exact when the path is combined into a
+/// [`Compound`](crate::shape::Compound) or [`Boolean`](crate::shape::Boolean) shape.
+fn transform_path(
+    (path, bb): (Path, BoundingBox),
+    func: impl Fn(Coordinate) -> Coordinate,
+) -> (Path, BoundingBox) {
+    (map_path(path, &func), bb.map(func))
+}
+
+/// Apply `func` to the outline of a path
+fn map_path(path: Path, func: impl Fn(Coordinate) -> Coordinate) -> Path {
+    let data = BezierPath::from_path(&path).map(func).to_data();
+    path.set("d", data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, shape::Polygon};
+
+    #[test]
+    fn nested_opacities_multiply_on_the_path_and_the_element() {
+        let shape = Polygon::builder()
+            .sides(4)
+            .size(10.0)
+            .color(Color::new(0, 0, 0, 255))
+            .build()
+            .opacity_fn(|_| 0.5)
+            .opacity_fn(|_| 0.5);
+
+        let context = Context::new((), Coordinate::origin(), (100.0, 100.0), 1, 0);
+        let (path, element, _) = shape.generate_path_element_and_bb(&context);
+        assert_eq!(path.get_attributes()["opacity"].to_string(), "0.250");
+
+        let element = element.to_string();
+        assert_eq!(
+            element.matches(r#"opacity="0.500""#).count(),
+            2,
+            "{element}"
+        );
+    }
+
+    #[test]
+    fn the_outer_blend_mode_replaces_the_inner_one_on_the_path() {
+        let shape = Polygon::builder()
+            .sides(4)
+            .size(10.0)
+            .color(Color::new(0, 0, 0, 255))
+            .build()
+            .isolate()
+            .blend_mode(BlendMode::Multiply)
+            .blend_mode(BlendMode::Screen);
+
+        let context = Context::new((), Coordinate::origin(), (100.0, 100.0), 1, 0);
+        let (path, element, _) = shape.generate_path_element_and_bb(&context);
+        // A single path has nothing to isolate, so only the element is isolated
+        assert_eq!(
+            path.get_attributes()["style"].to_string(),
+            "mix-blend-mode:screen"
+        );
+
+        let element = element.to_string();
+        assert!(element.contains("isolation:isolate"), "{element}");
+    }
+
+    #[test]
+    fn other_style_declarations_are_kept() {
+        let path = Path::new().set("style", "stroke-linecap:round;mix-blend-mode:multiply");
+
+        assert_eq!(
+            set_style(path, "mix-blend-mode", "screen").get_attributes()["style"].to_string(),
+            "stroke-linecap:round;mix-blend-mode:screen"
+        );
+    }
+}
@@ -1,19 +1,117 @@
+/// Implement a builder setter that takes a static value, in terms of the setter taking a function
+/// of `Index`
+macro_rules! impl_static_setter {
+    ($builder:ident, $method:ident -> $fn_method:ident: $type:ty, $state:ident, $field:ident) => {
+        pub fn $method(self, value: $type) -> $builder<I, $state<S>>
+        where
+            S::$field: IsUnset,
+        {
+            self.$fn_method(move |_| value)
+        }
+    };
+}
+
+/// Implement a builder setter that takes a function of `Index`, in terms of the setter taking a
+/// function of [`Context`]
+macro_rules! impl_index_setter {
+    ($builder:ident, $method:ident -> $context_method:ident: $type:ty, $state:ident, $field:ident) => {
+        pub fn $method(self, func: impl Fn(&I) -> $type + 'static) -> $builder<I, $state<S>>
+        where
+            S::$field: IsUnset,
+        {
+            self.$context_method(move |context| func(&context.index))
+        }
+    };
+}
+
+mod boolean;
+mod choice;
+mod compound;
+mod connector;
+pub(crate) mod context;
+mod decorator;
+mod parametric;
 mod polygon;
+mod stack;
+mod stamp;
+mod superformula;
+mod text;
+mod truchet;
 
-pub use polygon::Polygon;
-use svg::node::element::Path;
+pub use boolean::{Boolean, Operation};
+pub use choice::{Selector, ShapeChoice};
+pub use compound::Compound;
+pub use connector::Connector;
+pub use context::Context;
+pub use decorator::{
+    Blended, Filtered, Isolated, Offset, Opacity, Rotate, Scale, ShapeExt, Visible,
+};
+pub use parametric::{Curve, Parametric};
+pub use polygon::{CornerStyle, Jitter, Polygon};
+pub use stack::{ShapeStack, StackLayer};
+pub use stamp::Stamp;
+pub use superformula::Superformula;
+pub use text::{Font, Text};
+pub use truchet::{Motif, Style, Tiling, Truchet};
+
+use svg::node::element::{Element, Path};
 
 use crate::geometry::BoundingBox;
 
 pub type IndexFn<I, T> = Box<dyn Fn(&I) -> T>;
 
+/// A function of the [`Context`] a shape is drawn in
+pub type ContextFn<I, T> = Box<dyn Fn(&Context<I>) -> T>;
+
 /// A trait for defining a shape
 pub trait Shape {
     type Index;
 
-    /// Generate an SVG `path` for a given `index`
+    /// Generate an SVG `path` for a given `context`
+    ///
+    /// The `context` contains the `index` of the point, which can be used by implementers to
+    /// control the generated shape based on where it is in the
+    /// [`PointSet`](crate::point_set::PointSet). It also describes where the point is on the
+    /// [`Canvas`](crate::Canvas), so that shapes can vary across the canvas regardless of the
+    /// `PointSet` used.
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox);
+
+    /// Generate an SVG element for a given `context`
+    ///
+    /// This is what the [`Canvas`](crate::Canvas) draws. By default it is the `path` from
+    /// [`generate_path_and_bb`](Shape::generate_path_and_bb), but shapes made of several paths,
+    /// such as a [`ShapeStack`], can return a group instead.
+    fn generate_element_and_bb(&self, context: &Context<Self::Index>) -> (Element, BoundingBox) {
+        let (path, bb) = self.generate_path_and_bb(context);
+        (path.into(), bb)
+    }
+
+    /// Generate both the `path` and the element for a given `context`, generating the shape once
+    ///
+    /// The bounding box is the one of the element. The [`Canvas`](crate::Canvas) uses this for
+    /// layers that resolve overlaps, which need the outline of the shape as well as what is
+    /// drawn. By default the element is the `path` itself, so shapes that override
+    /// [`generate_element_and_bb`](Shape::generate_element_and_bb) should override this too.
+    fn generate_path_element_and_bb(
+        &self,
+        context: &Context<Self::Index>,
+    ) -> (Path, Element, BoundingBox) {
+        let (path, bb) = self.generate_path_and_bb(context);
+        (path.clone(), path.into(), bb)
+    }
+
+    /// A bounding box containing the shape for a given `context`, found without generating it
+    ///
+    /// The box can be larger than the shape, but must contain the bounding box from
+    /// [`generate_element_and_bb`](Shape::generate_element_and_bb). The
+    /// [`Canvas`](crate::Canvas) uses it to skip shapes outside of it before generating them. By
+    /// default it is `None`, for shapes whose bounds aren't known until they are generated.
     ///
-    /// This `index` can be used by implementers to control the generated shape based on where it
-    /// is in the [`PointSet`](crate::point_set::PointSet).
-    fn generate_path_and_bb(&self, index: &Self::Index) -> (Path, BoundingBox);
+    /// The bounds are found separately from the shape itself, so the functions of the shape must
+    /// give the same result each time they are called with the same `context`. Shapes should use
+    /// [`Context::rng`] for randomness rather than an unseeded generator.
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        let _ = context;
+        None
+    }
 }
@@ -0,0 +1,211 @@
+//! A shape drawn by tracing a parametric curve
+use std::f64::consts::PI;
+
+use svg::node::element::Path;
+
+use crate::{
+    Color,
+    geometry::{Angle, BezierPath, BoundingBox, Coordinate, curve},
+    shape::{Context, ContextFn, Shape},
+};
+use parametric_builder::{IsUnset, SetColorFn, SetCurveFn, SetRotationFn, SetSizeFn, State};
+
+/// How far the smoothed path may stray from the curve, in pixels
+const TOLERANCE: f64 = 0.25;
+
+/// A closed parametric curve
+#[derive(Debug, Clone, Copy)]
+pub enum Curve {
+    /// A [rose](https://en.wikipedia.org/wiki/Rose_(mathematics)) with `r = cos(n/d · θ)`
+    Rose { n: u32, d: u32 },
+
+    /// A [hypotrochoid](https://en.wikipedia.org/wiki/Hypotrochoid), as drawn by a spirograph
+    ///
+    /// A circle of `rolling_radius` rolls around the inside of a circle of `fixed_radius`,
+    /// tracing a point `distance` from the center of the rolling circle.
+    Hypotrochoid {
+        fixed_radius: u32,
+        rolling_radius: u32,
+        distance: f64,
+    },
+
+    /// A [Lissajous curve](https://en.wikipedia.org/wiki/Lissajous_curve) with
+    /// `x = sin(a·t + δ)` and `y = sin(b·t)`
+    Lissajous { a: u32, b: u32, delta: Angle },
+
+    /// A custom curve, which should return to its starting point after `period`
+    Custom {
+        func: fn(f64) -> Coordinate,
+        period: f64,
+    },
+}
+
+impl Curve {
+    /// The point on the curve at `t`
+    pub fn point(&self, t: f64) -> Coordinate {
+        match *self {
+            Curve::Rose { n, d } => Coordinate::Polar {
+                r: (f64::from(n) / f64::from(d) * t).cos(),
+                phi: Angle::Radian(t),
+            },
+            Curve::Hypotrochoid {
+                fixed_radius,
+                rolling_radius,
+                distance,
+            } => {
+                let (big_r, r) = (f64::from(fixed_radius), f64::from(rolling_radius));
+                Coordinate::Cartesian {
+                    x: (big_r - r) * t.cos() + distance * ((big_r - r) / r * t).cos(),
+                    y: (big_r - r) * t.sin() - distance * ((big_r - r) / r * t).sin(),
+                }
+            }
+            Curve::Lissajous { a, b, delta } => Coordinate::Cartesian {
+                x: (f64::from(a) * t + delta.to_radian()).sin(),
+                y: (f64::from(b) * t).sin(),
+            },
+            Curve::Custom { func, .. } => func(t),
+        }
+    }
+
+    /// The smallest `t` after which the curve repeats
+    pub fn period(&self) -> f64 {
+        match *self {
+            Curve::Rose { n, d } => {
+                let divisor = gcd(n, d).max(1);
+                let (n, d) = (n / divisor, d / divisor);
+                if n % 2 == 1 && d % 2 == 1 {
+                    PI * f64::from(d)
+                } else {
+                    2.0 * PI * f64::from(d)
+                }
+            }
+            Curve::Hypotrochoid {
+                fixed_radius,
+                rolling_radius,
+                ..
+            } => 2.0 * PI * f64::from(rolling_radius / gcd(fixed_radius, rolling_radius).max(1)),
+            Curve::Lissajous { .. } => 2.0 * PI,
+            Curve::Custom { period, .. } => period,
+        }
+    }
+}
+
+/// A shape drawn by tracing a closed parametric [`Curve`]
+///
+/// The curve is sampled more densely where it bends, and drawn as a smooth path through the
+/// samples. Areas where the curve crosses itself are filled using the even-odd rule. Like
+/// [`Polygon`](crate::shape::Polygon), its fields are defined as functions of `Index`, or of the
+/// [`Context`] for setters ending in `_context_fn`.
+#[derive(bon::Builder)]
+pub struct Parametric<I> {
+    /// The curve that is traced
+    #[builder(
+        setters(name = curve_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Curve + 'static| Box::new(func)
+    )]
+    pub curve_fn: ContextFn<I, Curve>,
+
+    /// The size of the shape
+    ///
+    /// This is the diameter of the smallest circle centered on the point that encloses the shape.
+    #[builder(
+        setters(name = size_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub size_fn: ContextFn<I, f64>,
+
+    /// The rotation of the shape
+    #[builder(
+        default = (Box::new(|_| Angle::default())),
+        setters(name = rotation_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Angle + 'static| Box::new(func)
+    )]
+    pub rotation_fn: ContextFn<I, Angle>,
+
+    /// The color of the shape
+    #[builder(
+        setters(name = color_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Color + 'static| Box::new(func)
+    )]
+    pub color_fn: ContextFn<I, Color>,
+}
+
+impl<I> Shape for Parametric<I> {
+    type Index = I;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let curve = (self.curve_fn)(context);
+        let size = (self.size_fn)(context) / 2.0;
+        let rotation = (self.rotation_fn)(context);
+        let period = curve.period();
+
+        // A rough size from evenly spaced points, used to scale the sampling tolerance
+        let rough_radius = (0..360)
+            .map(|i| {
+                curve
+                    .point(period * f64::from(i) / 360.0)
+                    .to_polar()
+                    .0
+                    .abs()
+            })
+            .filter(|r| r.is_finite())
+            .fold(0.0, f64::max);
+        if !(rough_radius > 0.0 && size > 0.0 && period.is_finite() && period > 0.0) {
+            return (Path::new(), BoundingBox::empty());
+        }
+
+        // The curve is sampled before it is scaled, so the tolerance is scaled to match
+        let points = curve::sample_closed_curve(
+            |t| {
+                let (r, phi) = curve.point(t).to_polar();
+                Coordinate::Polar {
+                    r,
+                    phi: phi + rotation,
+                }
+            },
+            0.0,
+            period,
+            TOLERANCE * rough_radius / size,
+        );
+
+        // The largest radius is taken from the points that are drawn, so the size is exact
+        let max_radius = points
+            .iter()
+            .map(|point| point.to_polar().0.abs())
+            .filter(|r| r.is_finite())
+            .fold(0.0, f64::max);
+        let scale = size / max_radius;
+        let points: Vec<_> = points.into_iter().map(|point| point * scale).collect();
+
+        // The spline can bulge past the points it passes through
+        let data = curve::smooth_closed_data(&points);
+        let bb = BezierPath::from_data(&data).bounding_box();
+
+        let color = (self.color_fn)(context);
+        (
+            Path::new()
+                .set("stroke", "none")
+                .set("d", data)
+                .set("fill", color.to_svg_color())
+                .set("fill-opacity", color.to_opacity_percent())
+                .set("fill-rule", "evenodd"),
+            bb,
+        )
+    }
+}
+
+impl<I, S: State> ParametricBuilder<I, S> {
+    impl_index_setter!(ParametricBuilder, curve_fn -> curve_context_fn: Curve, SetCurveFn, CurveFn);
+    impl_index_setter!(ParametricBuilder, size_fn -> size_context_fn: f64, SetSizeFn, SizeFn);
+    impl_index_setter!(ParametricBuilder, rotation_fn -> rotation_context_fn: Angle, SetRotationFn, RotationFn);
+    impl_index_setter!(ParametricBuilder, color_fn -> color_context_fn: Color, SetColorFn, ColorFn);
+
+    impl_static_setter!(ParametricBuilder, curve -> curve_fn: Curve, SetCurveFn, CurveFn);
+    impl_static_setter!(ParametricBuilder, size -> size_fn: f64, SetSizeFn, SizeFn);
+    impl_static_setter!(ParametricBuilder, rotation -> rotation_fn: Angle, SetRotationFn, RotationFn);
+    impl_static_setter!(ParametricBuilder, color -> color_fn: Color, SetColorFn, ColorFn);
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
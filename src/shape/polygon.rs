@@ -1,51 +1,156 @@
 //! A polygonal shape with rounded corners
+use rand::{Rng, RngExt};
 use rand_distr::{Distribution, multi::Dirichlet};
 use std::f64::consts::PI;
 use svg::node::element::{Path, path::Data};
 
 use itertools::Itertools;
-use rand_distr::Normal;
+use rand_distr::{LogNormal, Normal};
 
 use crate::{
     Color,
     geometry::{Angle, BoundingBox, Coordinate},
-    shape::{IndexFn, Shape},
+    shape::{Context, ContextFn, Shape},
 };
-use polygon_builder::{IsUnset, SetColorFn, SetCvFn, SetRotationFn, SetSidesFn, SetSizeFn, State};
+use polygon_builder::{
+    IsUnset, SetColorFn, SetCornerStyleFn, SetCvFn, SetRotationFn, SetSideJitterFn, SetSidesFn,
+    SetSizeFn, State,
+};
+
+/// The smallest Dirichlet concentration used when randomizing the corners
+///
+/// Smaller values give more irregular polygons, but must be positive.
+const MIN_ALPHA: f64 = 0.01;
 
 /// A polygonal shape with rounded corners
 ///
-/// Its fields are defined as functions of `Index`. This allows the polygon to be
-/// rendered dynamically depending on where it is in the image.
+/// Its fields are defined as functions of `Index`, or of the [`Context`] for setters ending in
+/// `_context_fn`. This allows the polygon to be rendered dynamically depending on where it is in
+/// the image.
 #[derive(bon::Builder)]
 pub struct Polygon<I> {
     /// The number of sides in the polygon
-    #[builder(with = |func: impl Fn(&I) -> u8 + 'static| Box::new(func))]
-    pub sides_fn: IndexFn<I, u8>,
+    #[builder(
+        setters(name = sides_context_fn),
+        with = |func: impl Fn(&Context<I>) -> u8 + 'static| Box::new(func)
+    )]
+    pub sides_fn: ContextFn<I, u8>,
 
     /// The size of the polygon
     ///
     /// This is the diameter of the circle that the vertices of the polygon would lie on.
-    #[builder(with = |func: impl Fn(&I) -> f64 + 'static| Box::new(func))]
-    pub size_fn: IndexFn<I, f64>,
+    #[builder(
+        setters(name = size_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub size_fn: ContextFn<I, f64>,
 
     /// The rotation of the polygon
     ///
     /// With no rotation, the shape is rendered "pointy side up".
     #[builder(
         default = (Box::new(|_| Angle::default())),
-        with = |func: impl Fn(&I) -> Angle + 'static| Box::new(func)
+        setters(name = rotation_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Angle + 'static| Box::new(func)
     )]
-    // #[builder(with = |func: impl  Fn(&I) -> Angle + 'static| Box::new(func) as IndexFn<I, Angle>)]
-    pub rotation_fn: IndexFn<I, Angle>,
+    pub rotation_fn: ContextFn<I, Angle>,
 
     /// The color of the polygon
-    #[builder(with = |func: impl Fn(&I) -> Color + 'static| Box::new(func))]
-    pub color_fn: IndexFn<I, Color>,
+    #[builder(
+        setters(name = color_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Color + 'static| Box::new(func)
+    )]
+    pub color_fn: ContextFn<I, Color>,
 
     /// The coefficient of variance used when randomizing the shape of the polygon
-    #[builder(with = |func: impl Fn(&I) -> f64 + 'static| Box::new(func))]
-    pub cv_fn: Option<IndexFn<I, f64>>,
+    ///
+    /// Any value can be used. Values that are too large for the number of sides are treated as
+    /// the largest possible irregularity.
+    #[builder(
+        setters(name = cv_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub cv_fn: Option<ContextFn<I, f64>>,
+
+    /// How the corners of the polygon are drawn
+    #[builder(
+        default = (Box::new(|_| CornerStyle::default())),
+        setters(name = corner_style_context_fn),
+        with = |func: impl Fn(&Context<I>) -> CornerStyle + 'static| Box::new(func)
+    )]
+    pub corner_style_fn: ContextFn<I, CornerStyle>,
+
+    /// How far along each side the corners start and end
+    ///
+    /// Sampled values are clamped to `0.1..=0.9`, where `0.5` is the midpoint of the side.
+    #[builder(
+        default = (Box::new(|_| Jitter::default())),
+        setters(name = side_jitter_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Jitter + 'static| Box::new(func)
+    )]
+    pub side_jitter_fn: ContextFn<I, Jitter>,
+}
+
+/// How the corners of a [`Polygon`] are drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CornerStyle {
+    /// Straight lines meeting at the corner
+    Sharp,
+
+    /// A curve pulled towards the corner with the given tension
+    ///
+    /// A tension of `1.0` pulls the curve all the way to the corner, and `0.0` cuts the corner
+    /// with a straight line.
+    Rounded(f64),
+
+    /// A circular arc meeting both sides of the corner
+    Circular,
+}
+
+impl Default for CornerStyle {
+    fn default() -> Self {
+        Self::Rounded(1.0)
+    }
+}
+
+/// A distribution for the position of points along the sides of a [`Polygon`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Jitter {
+    /// Always at the given position
+    Fixed(f64),
+
+    /// Normally distributed around `mean`
+    Normal { mean: f64, std_dev: f64 },
+
+    /// Uniformly distributed between `min` and `max`
+    Uniform { min: f64, max: f64 },
+}
+
+impl Default for Jitter {
+    fn default() -> Self {
+        Self::Normal {
+            mean: 0.5,
+            std_dev: 0.1,
+        }
+    }
+}
+
+impl Jitter {
+    fn sample(self, rng: &mut impl Rng) -> f64 {
+        let t = match self {
+            Jitter::Fixed(t) => t,
+            Jitter::Normal { mean, std_dev } => Normal::new(mean, std_dev.abs())
+                .map(|normal| normal.sample(rng))
+                .unwrap_or(mean),
+            Jitter::Uniform { min, max } => min + rng.random::<f64>() * (max - min),
+        };
+
+        if t.is_finite() {
+            t.clamp(0.1, 0.9)
+        } else {
+            0.5
+        }
+    }
 }
 
 impl<I> Polygon<I> {
@@ -58,11 +163,15 @@ impl<I> Polygon<I> {
         cv_fn: Option<impl Fn(&I) -> f64 + 'static>,
     ) -> Self {
         Self {
-            sides_fn: Box::new(sides_fn),
-            size_fn: Box::new(size_fn),
-            rotation_fn: Box::new(rotation_fn),
-            color_fn: Box::new(color_fn),
-            cv_fn: cv_fn.map(|f| Box::new(f) as Box<dyn Fn(&I) -> f64>),
+            sides_fn: Box::new(move |context| sides_fn(&context.index)),
+            size_fn: Box::new(move |context| size_fn(&context.index)),
+            rotation_fn: Box::new(move |context| rotation_fn(&context.index)),
+            color_fn: Box::new(move |context| color_fn(&context.index)),
+            cv_fn: cv_fn.map(|f| {
+                Box::new(move |context: &Context<I>| f(&context.index)) as ContextFn<I, f64>
+            }),
+            corner_style_fn: Box::new(|_| CornerStyle::default()),
+            side_jitter_fn: Box::new(|_| Jitter::default()),
         }
     }
 
@@ -83,20 +192,23 @@ impl<I> Polygon<I> {
         )
     }
 
-    fn generate_corner_coordinates(&self, index: &I) -> Vec<Coordinate> {
-        let sides = (self.sides_fn)(index);
-        let size = (self.size_fn)(index) / 2.0;
-        let rotation = Angle::Radian(-PI / 2.0) + (self.rotation_fn)(index);
-
-        let divisions = if let Some(cv_fn) = &self.cv_fn {
-            let cv = cv_fn(index);
-            let alpha = (f64::from(sides) - 1_f64 - cv.powi(2)) / (f64::from(sides) * cv.powi(2));
-            let params = vec![alpha; sides as usize];
-            let dirichlet = Dirichlet::new(&params).unwrap();
-            dirichlet.sample(&mut rand::rng())
-        } else {
-            vec![1.0 / f64::from(sides); sides as usize]
-        };
+    fn generate_corner_coordinates(
+        &self,
+        context: &Context<I>,
+        rng: &mut impl Rng,
+    ) -> Vec<Coordinate> {
+        let sides = (self.sides_fn)(context);
+        let size = (self.size_fn)(context) / 2.0;
+        let rotation = Angle::Radian(-PI / 2.0) + (self.rotation_fn)(context);
+        let cv = self
+            .cv_fn
+            .as_ref()
+            .map(|cv_fn| cv_fn(context).abs())
+            .filter(|cv| cv.is_finite() && 0.0 < *cv);
+
+        let divisions = cv
+            .and_then(|cv| Self::sample_divisions(sides, cv, rng))
+            .unwrap_or_else(|| vec![1.0 / f64::from(sides); sides as usize]);
 
         divisions
             .iter()
@@ -105,13 +217,10 @@ impl<I> Polygon<I> {
                 Some(*state)
             })
             .map(|theta| {
-                let r = if let Some(cv_fn) = &self.cv_fn {
-                    let cv = cv_fn(index);
-                    let normal = Normal::new(size, cv * size).unwrap();
-                    normal.sample(&mut rand::rng())
-                } else {
-                    size
-                };
+                // A log-normal distribution keeps the radius positive for any `cv`
+                let r = cv
+                    .and_then(|cv| LogNormal::from_mean_cv(size, cv).ok())
+                    .map_or(size, |log_normal| log_normal.sample(rng));
 
                 Coordinate::Polar {
                     r,
@@ -121,14 +230,31 @@ impl<I> Polygon<I> {
             .collect()
     }
 
-    fn generate_side_coordinates(corner_coordinates: &[Coordinate]) -> Vec<Coordinate> {
-        let normal = Normal::new(0.5, 0.1).unwrap();
-        let mut rng = rand::rng();
+    /// Sample how the angle around the polygon is divided between its sides
+    ///
+    /// Each division is Dirichlet distributed with the coefficient of variance `cv`. The largest
+    /// coefficient of variance possible is `√(sides - 1)`, so larger values are clamped to just
+    /// under it.
+    fn sample_divisions(sides: u8, cv: f64, rng: &mut impl Rng) -> Option<Vec<f64>> {
+        let n = f64::from(sides);
+        let alpha = ((n - 1.0 - cv.powi(2)) / (n * cv.powi(2))).max(MIN_ALPHA);
+
+        let divisions = Dirichlet::new(&vec![alpha; sides as usize])
+            .ok()?
+            .sample(rng);
 
+        divisions.iter().all(|x| x.is_finite()).then_some(divisions)
+    }
+
+    fn generate_side_coordinates(
+        corner_coordinates: &[Coordinate],
+        side_jitter: Jitter,
+        rng: &mut impl Rng,
+    ) -> Vec<Coordinate> {
         let mut side_coordinates: Vec<_> = corner_coordinates
             .iter()
             .circular_tuple_windows()
-            .map(|(c1, c2)| c1.lerp(c2, (normal.sample(&mut rng) as f64).clamp(0.1, 0.9)))
+            .map(|(c1, c2)| c1.lerp(c2, side_jitter.sample(rng)))
             .collect();
         side_coordinates.rotate_right(1);
 
@@ -138,24 +264,21 @@ impl<I> Polygon<I> {
     fn generate_path(
         &self,
         color: &Color,
+        corner_style: CornerStyle,
         corner_coordinates: &[Coordinate],
         side_coordinates: &[Coordinate],
     ) -> Path {
         let mut data = Data::new();
 
         if let Some(first) = side_coordinates.first() {
-            let (x, y) = first.to_rounded_cartesian(3);
-            data = data.move_to((x, y));
+            data = data.move_to(first.to_rounded_cartesian(3));
 
-            for (end, corner) in side_coordinates
+            for ((start, end), corner) in side_coordinates
                 .iter()
-                .skip(1)
-                .chain(side_coordinates.first())
+                .circular_tuple_windows()
                 .zip(corner_coordinates.iter())
             {
-                let (end_x, end_y) = end.to_rounded_cartesian(3);
-                let (corner_x, corner_y) = corner.to_rounded_cartesian(3);
-                data = data.cubic_curve_to((corner_x, corner_y, corner_x, corner_y, end_x, end_y));
+                data = Self::corner_to(data, corner_style, start, corner, end);
             }
         }
 
@@ -166,6 +289,63 @@ impl<I> Polygon<I> {
             .set("fill-opacity", color.to_opacity_percent())
     }
 
+    /// Draw the corner at `corner`, from the point on the previous side at `start` to the point
+    /// on the next side at `end`
+    fn corner_to(
+        data: Data,
+        corner_style: CornerStyle,
+        start: &Coordinate,
+        corner: &Coordinate,
+        end: &Coordinate,
+    ) -> Data {
+        match corner_style {
+            CornerStyle::Sharp => data
+                .line_to(corner.to_rounded_cartesian(3))
+                .line_to(end.to_rounded_cartesian(3)),
+            CornerStyle::Rounded(tension) => {
+                let tension = if tension.is_finite() {
+                    tension.clamp(0.0, 1.0)
+                } else {
+                    1.0
+                };
+                let (c1_x, c1_y) = start.lerp(corner, tension).to_rounded_cartesian(3);
+                let (c2_x, c2_y) = end.lerp(corner, tension).to_rounded_cartesian(3);
+                let (end_x, end_y) = end.to_rounded_cartesian(3);
+
+                data.cubic_curve_to((c1_x, c1_y, c2_x, c2_y, end_x, end_y))
+            }
+            CornerStyle::Circular => {
+                let (start_len, end_len) = (corner.dist(start), corner.dist(end));
+                let len = start_len.min(end_len);
+                let (start_x, start_y) = (*start - *corner).to_cartesian();
+                let (end_x, end_y) = (*end - *corner).to_cartesian();
+                let cos_theta =
+                    ((start_x * end_x + start_y * end_y) / (start_len * end_len)).clamp(-1.0, 1.0);
+                let radius = len * (cos_theta.acos() / 2.0).tan();
+
+                // Sides that are (nearly) straight at the corner have no arc to draw
+                if !(radius.is_finite() && 0.0 < radius) || cos_theta < -0.9999 {
+                    return data.line_to(end.to_rounded_cartesian(3));
+                }
+
+                let tangent_start = corner.lerp(start, len / start_len);
+                let (tangent_x, tangent_y) =
+                    corner.lerp(end, len / end_len).to_rounded_cartesian(3);
+
+                // The arc bends clockwise if the path turns clockwise at the corner
+                let sweep = if start_x * end_y - start_y * end_x < 0.0 {
+                    1
+                } else {
+                    0
+                };
+
+                data.line_to(tangent_start.to_rounded_cartesian(3))
+                    .elliptical_arc_to((radius, radius, 0, 0, sweep, tangent_x, tangent_y))
+                    .line_to(end.to_rounded_cartesian(3))
+            }
+        }
+    }
+
     fn generate_bb(&self, corner_coordinates: &[Coordinate]) -> BoundingBox {
         BoundingBox::from_points(corner_coordinates)
     }
@@ -174,33 +354,58 @@ impl<I> Polygon<I> {
 impl<I: Copy> Shape for Polygon<I> {
     type Index = I;
 
-    fn generate_path_and_bb(&self, index: &Self::Index) -> (Path, BoundingBox) {
-        let corner_coordinates = self.generate_corner_coordinates(index);
-        let side_coordinates = Self::generate_side_coordinates(&corner_coordinates);
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        // Seeding from the context lets shapes sharing a seed wobble together
+        let mut rng = context.rng();
+        let corner_coordinates = self.generate_corner_coordinates(context, &mut rng);
+        let side_coordinates = Self::generate_side_coordinates(
+            &corner_coordinates,
+            (self.side_jitter_fn)(context),
+            &mut rng,
+        );
 
-        let color = (self.color_fn)(index);
+        let color = (self.color_fn)(context);
+        let corner_style = (self.corner_style_fn)(context);
         (
-            self.generate_path(&color, &corner_coordinates, &side_coordinates),
+            self.generate_path(&color, corner_style, &corner_coordinates, &side_coordinates),
             self.generate_bb(&corner_coordinates),
         )
     }
-}
 
-macro_rules! impl_static_setter {
-    ($method:ident -> $fn_method:ident: $type:ty, $state:ident, $field:ident) => {
-        pub fn $method(self, value: $type) -> PolygonBuilder<I, $state<S>>
-        where
-            S::$field: IsUnset,
-        {
-            self.$fn_method(move |_| value)
+    /// The box around the circle the corners lie on, or `None` for randomized polygons, whose
+    /// corners can be any distance from the center
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        let is_randomized = self
+            .cv_fn
+            .as_ref()
+            .is_some_and(|cv_fn| cv_fn(context) != 0.0);
+        if is_randomized {
+            return None;
         }
-    };
+
+        let radius = (self.size_fn)(context).abs() / 2.0;
+        let corner = Coordinate::Cartesian {
+            x: radius,
+            y: radius,
+        };
+        Some(BoundingBox::new(Coordinate::origin() - corner, corner))
+    }
 }
 
 impl<I, S: State> PolygonBuilder<I, S> {
-    impl_static_setter!(sides -> sides_fn: u8, SetSidesFn, SidesFn);
-    impl_static_setter!(size -> size_fn: f64, SetSizeFn, SizeFn);
-    impl_static_setter!(rotation -> rotation_fn: Angle, SetRotationFn, RotationFn);
-    impl_static_setter!(color -> color_fn: Color, SetColorFn, ColorFn);
-    impl_static_setter!(cv -> cv_fn: f64, SetCvFn, CvFn);
+    impl_index_setter!(PolygonBuilder, sides_fn -> sides_context_fn: u8, SetSidesFn, SidesFn);
+    impl_index_setter!(PolygonBuilder, size_fn -> size_context_fn: f64, SetSizeFn, SizeFn);
+    impl_index_setter!(PolygonBuilder, rotation_fn -> rotation_context_fn: Angle, SetRotationFn, RotationFn);
+    impl_index_setter!(PolygonBuilder, color_fn -> color_context_fn: Color, SetColorFn, ColorFn);
+    impl_index_setter!(PolygonBuilder, cv_fn -> cv_context_fn: f64, SetCvFn, CvFn);
+    impl_index_setter!(PolygonBuilder, corner_style_fn -> corner_style_context_fn: CornerStyle, SetCornerStyleFn, CornerStyleFn);
+    impl_index_setter!(PolygonBuilder, side_jitter_fn -> side_jitter_context_fn: Jitter, SetSideJitterFn, SideJitterFn);
+
+    impl_static_setter!(PolygonBuilder, sides -> sides_fn: u8, SetSidesFn, SidesFn);
+    impl_static_setter!(PolygonBuilder, size -> size_fn: f64, SetSizeFn, SizeFn);
+    impl_static_setter!(PolygonBuilder, rotation -> rotation_fn: Angle, SetRotationFn, RotationFn);
+    impl_static_setter!(PolygonBuilder, color -> color_fn: Color, SetColorFn, ColorFn);
+    impl_static_setter!(PolygonBuilder, cv -> cv_fn: f64, SetCvFn, CvFn);
+    impl_static_setter!(PolygonBuilder, corner_style -> corner_style_fn: CornerStyle, SetCornerStyleFn, CornerStyleFn);
+    impl_static_setter!(PolygonBuilder, side_jitter -> side_jitter_fn: Jitter, SetSideJitterFn, SideJitterFn);
 }
@@ -0,0 +1,255 @@
+//! A group of shapes drawn together as a single shape
+use svg::node::element::{Element, Group, Path};
+
+use crate::{
+    geometry::{Angle, BezierPath, BoundingBox, Coordinate},
+    shape::{Context, Shape},
+};
+
+/// A shape in a [`ShapeStack`], placed relative to the center of the stack
+pub struct StackLayer<I> {
+    /// The [`Shape`] that is drawn
+    pub shape: Box<dyn Shape<Index = I>>,
+
+    /// The offset of the shape from the center of the stack
+    pub offset: Coordinate,
+
+    /// The scale of the shape
+    pub scale: f64,
+
+    /// The rotation of the shape around the center of the stack
+    pub rotation: Angle,
+}
+
+impl<I> StackLayer<I> {
+    /// Define a new layer for `shape`, without any offset, scaling or rotation
+    pub fn new(shape: impl Shape<Index = I> + 'static) -> Self {
+        Self {
+            shape: Box::new(shape),
+            offset: Coordinate::origin(),
+            scale: 1.0,
+            rotation: Angle::default(),
+        }
+    }
+
+    /// Set the offset of the shape from the center of the stack
+    pub fn offset(&mut self, offset: impl Into<Coordinate>) -> &mut Self {
+        self.offset = offset.into();
+        self
+    }
+
+    /// Set the scale of the shape
+    pub fn scale(&mut self, scale: f64) -> &mut Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Set the rotation of the shape around the center of the stack
+    pub fn rotation(&mut self, rotation: Angle) -> &mut Self {
+        self.rotation = rotation;
+        self
+    }
+
+    fn is_identity(&self) -> bool {
+        let (x, y) = self.offset.to_cartesian();
+        x == 0.0 && y == 0.0 && self.scale == 1.0 && self.rotation.to_radian() == 0.0
+    }
+
+    /// Scale, rotate, then offset a coordinate of the shape
+    fn transform(&self, coordinate: Coordinate) -> Coordinate {
+        (coordinate * self.scale).rotate(self.rotation) + self.offset
+    }
+
+    /// Scale, rotate, then offset the outline of a path of the shape
+    fn transform_outline(&self, path: &Path) -> BezierPath {
+        BezierPath::from_path(path).map(|c| self.transform(c))
+    }
+
+    /// Scale, rotate, then offset an element of the shape with a group
+    fn place(&self, element: Element) -> Element {
+        if self.is_identity() {
+            return element;
+        }
+
+        let (x, y) = self.offset.to_cartesian();
+        Group::new()
+            .set(
+                "transform",
+                format!(
+                    "translate({x:.3},{y:.3}) rotate({:.3}) scale({:.3})",
+                    self.rotation.to_degree(),
+                    self.scale
+                ),
+            )
+            .add(element)
+            .into()
+    }
+}
+
+/// A group of shapes drawn together as a single shape, such as the concentric rings of an amulet
+///
+/// Shapes are drawn from the lowest layer to the highest, each with its own offset, scale and
+/// rotation. Every layer is given the same [`Context`], including its `seed`, so randomized
+/// shapes like a [`Polygon`](crate::shape::Polygon) with a `cv` wobble together instead of
+/// independently.
+pub struct ShapeStack<I> {
+    /// The list of [`StackLayer`], ordered from lowest to highest
+    pub layers: Vec<StackLayer<I>>,
+}
+
+impl<I> ShapeStack<I> {
+    /// Define a new stack with no shapes
+    pub fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+
+    /// Add a shape on top of the stack
+    ///
+    /// The returned [`StackLayer`] can be used to place the shape within the stack.
+    pub fn add_shape(&mut self, shape: impl Shape<Index = I> + 'static) -> &mut StackLayer<I> {
+        self.layers.push(StackLayer::new(shape));
+        self.layers.last_mut().unwrap()
+    }
+}
+
+impl<I> Default for ShapeStack<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> Shape for ShapeStack<I> {
+    type Index = I;
+
+    /// Generate a single `path` with the outlines of every layer
+    ///
+    /// This is used when the stack is part of a [`Compound`](crate::shape::Compound) or
+    /// [`Boolean`](crate::shape::Boolean) shape, and takes its attributes from the first layer.
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let mut first_path = None;
+        let mut outline = BezierPath::default();
+        let mut bb = BoundingBox::empty();
+
+        for layer in &self.layers {
+            let (path, path_bb) = layer.shape.generate_path_and_bb(context);
+            outline
+                .segments
+                .extend(layer.transform_outline(&path).segments);
+            bb = bb + path_bb.map(|c| layer.transform(c));
+            first_path.get_or_insert(path);
+        }
+
+        match first_path {
+            Some(path) => (path.set("d", outline.to_data()), bb),
+            None => (Path::new(), bb),
+        }
+    }
+
+    fn generate_element_and_bb(&self, context: &Context<Self::Index>) -> (Element, BoundingBox) {
+        let (group, bb) = self.layers.iter().fold(
+            (Group::new(), BoundingBox::empty()),
+            |(group, bb), layer| {
+                let (element, element_bb) = layer.shape.generate_element_and_bb(context);
+                (
+                    group.add(layer.place(element)),
+                    bb + element_bb.map(|c| layer.transform(c)),
+                )
+            },
+        );
+
+        (group.into(), bb)
+    }
+
+    fn generate_path_element_and_bb(
+        &self,
+        context: &Context<Self::Index>,
+    ) -> (Path, Element, BoundingBox) {
+        let mut first_path = None;
+        let mut outline = BezierPath::default();
+        let mut group = Group::new();
+        let mut bb = BoundingBox::empty();
+
+        for layer in &self.layers {
+            let (path, element, element_bb) = layer.shape.generate_path_element_and_bb(context);
+            outline
+                .segments
+                .extend(layer.transform_outline(&path).segments);
+            group = group.add(layer.place(element));
+            bb = bb + element_bb.map(|c| layer.transform(c));
+            first_path.get_or_insert(path);
+        }
+
+        let path = first_path.map_or_else(Path::new, |path| path.set("d", outline.to_data()));
+        (path, group.into(), bb)
+    }
+
+    /// The bounds of every layer together, if they are all known
+    fn bounds(&self, context: &Context<Self::Index>) -> Option<BoundingBox> {
+        self.layers
+            .iter()
+            .try_fold(BoundingBox::empty(), |bb, layer| {
+                let layer_bb = layer.shape.bounds(context)?;
+                Some(bb + layer_bb.map(|c| layer.transform(c)))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Color,
+        effect::Effect,
+        shape::{Polygon, ShapeExt},
+    };
+
+    fn polygon() -> Polygon<()> {
+        Polygon::builder()
+            .sides(4)
+            .size(10.0)
+            .color(Color::new(0, 0, 0, 255))
+            .build()
+    }
+
+    #[test]
+    fn hidden_layers_have_empty_bounds() {
+        let mut stack = ShapeStack::new();
+        stack.add_shape(polygon().visible_fn(|_| false));
+        stack.add_shape(polygon().visible_fn(|_| false));
+
+        let context = Context::new((), Coordinate::origin(), (100.0, 100.0), 1, 0);
+        assert!(stack.bounds(&context).is_some_and(|bb| bb.is_empty()));
+    }
+
+    #[test]
+    fn bounds_contain_generated_bb() {
+        let mut stack = ShapeStack::new();
+        stack.add_shape(polygon());
+        stack.add_shape(polygon()).offset((20.0, 0.0));
+
+        let context = Context::new((), Coordinate::origin(), (100.0, 100.0), 1, 0);
+        let bounds = stack.bounds(&context).unwrap();
+        let (_, bb) = stack.generate_element_and_bb(&context);
+        assert!(bounds.min().to_cartesian().0 <= bb.min().to_cartesian().0);
+        assert!(bb.max().to_cartesian().0 <= bounds.max().to_cartesian().0);
+    }
+
+    #[test]
+    fn path_and_element_match_generating_them_apart() {
+        let mut stack = ShapeStack::new();
+        stack.add_shape(polygon().opacity_fn(|_| 0.5));
+        stack
+            .add_shape(polygon().effect(Effect::Blur { std_dev: 1.0 }))
+            .offset((20.0, 0.0));
+
+        let context = Context::new((), Coordinate::origin(), (100.0, 100.0), 1, 0);
+        let (path, element, bb) = stack.generate_path_element_and_bb(&context);
+        let (expected_element, expected_bb) = stack.generate_element_and_bb(&context);
+        assert_eq!(
+            path.to_string(),
+            stack.generate_path_and_bb(&context).0.to_string()
+        );
+        assert_eq!(element.to_string(), expected_element.to_string());
+        assert_eq!(format!("{bb:?}"), format!("{expected_bb:?}"));
+    }
+}
@@ -0,0 +1,94 @@
+//! A shape stamped from an arbitrary outline
+use svg::node::element::Path;
+
+use crate::{
+    Color,
+    geometry::{Angle, BezierPath, BoundingBox, FillRule},
+    shape::{Context, ContextFn, Shape},
+};
+use stamp_builder::{IsUnset, SetColorFn, SetRotationFn, SetSizeFn, State};
+
+/// A shape stamped from an arbitrary outline, such as a logo or kanji
+///
+/// The outline can be created from SVG path data with [`BezierPath::parse`], or loaded from an
+/// SVG file with [`BezierPath::from_svg_file`]. It is filled with the [`FillRule`] of the path,
+/// centered on each point, and scaled to the given size. Like
+/// [`Polygon`](crate::shape::Polygon), its fields are defined as functions of `Index`, or of the
+/// [`Context`] for setters ending in `_context_fn`.
+#[derive(bon::Builder)]
+pub struct Stamp<I> {
+    /// The outline that is stamped
+    pub path: BezierPath,
+
+    /// The size of the stamp
+    ///
+    /// This is the larger of the width and height of the outline.
+    #[builder(
+        setters(name = size_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub size_fn: ContextFn<I, f64>,
+
+    /// The rotation of the stamp
+    #[builder(
+        default = (Box::new(|_| Angle::default())),
+        setters(name = rotation_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Angle + 'static| Box::new(func)
+    )]
+    pub rotation_fn: ContextFn<I, Angle>,
+
+    /// The color of the stamp
+    #[builder(
+        setters(name = color_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Color + 'static| Box::new(func)
+    )]
+    pub color_fn: ContextFn<I, Color>,
+}
+
+impl<I> Shape for Stamp<I> {
+    type Index = I;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let path = fit_path(
+            &self.path,
+            (self.size_fn)(context),
+            (self.rotation_fn)(context),
+        );
+        let color = (self.color_fn)(context);
+
+        let mut element = Path::new()
+            .set("stroke", "none")
+            .set("d", path.to_data())
+            .set("fill", color.to_svg_color())
+            .set("fill-opacity", color.to_opacity_percent());
+        if path.fill_rule != FillRule::NonZero {
+            element = element.set("fill-rule", path.fill_rule.to_svg());
+        }
+
+        (element, path.bounding_box())
+    }
+}
+
+/// Center `path` on the origin, then scale its larger dimension to `size` and rotate it
+pub(crate) fn fit_path(path: &BezierPath, size: f64, rotation: Angle) -> BezierPath {
+    let bb = path.bounding_box();
+    let (width, height) = bb.span().to_cartesian();
+    let center = bb.min() + bb.span() / 2.0;
+    let scale = size / width.max(height);
+
+    if !scale.is_finite() {
+        return BezierPath::default();
+    }
+
+    path.map(|point| (point - center) * scale).rotate(rotation)
+}
+
+impl<I, S: State> StampBuilder<I, S> {
+    impl_index_setter!(StampBuilder, size_fn -> size_context_fn: f64, SetSizeFn, SizeFn);
+    impl_index_setter!(StampBuilder, rotation_fn -> rotation_context_fn: Angle, SetRotationFn, RotationFn);
+    impl_index_setter!(StampBuilder, color_fn -> color_context_fn: Color, SetColorFn, ColorFn);
+
+    impl_static_setter!(StampBuilder, size -> size_fn: f64, SetSizeFn, SizeFn);
+    impl_static_setter!(StampBuilder, rotation -> rotation_fn: Angle, SetRotationFn, RotationFn);
+    impl_static_setter!(StampBuilder, color -> color_fn: Color, SetColorFn, ColorFn);
+}
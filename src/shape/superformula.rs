@@ -0,0 +1,250 @@
+//! A shape drawn using the Gielis superformula
+use std::f64::consts::PI;
+
+use svg::node::element::Path;
+
+use crate::{
+    Color,
+    geometry::{Angle, BezierPath, BoundingBox, Coordinate, curve},
+    shape::{Context, ContextFn, Shape},
+};
+use superformula_builder::{
+    IsUnset, SetAFn, SetBFn, SetColorFn, SetMFn, SetN1Fn, SetN2Fn, SetN3Fn, SetRotationFn,
+    SetSizeFn, State,
+};
+
+/// How far the smoothed path may stray from the superformula, in pixels
+const TOLERANCE: f64 = 0.25;
+
+/// A shape drawn using the [Gielis superformula](https://en.wikipedia.org/wiki/Superformula)
+///
+/// The radius at each angle `φ` is `(|cos(mφ/4)/a|^n2 + |sin(mφ/4)/b|^n3)^(-1/n1)`. This can draw
+/// flowers, petals, stars and rounded polygons. Like [`Polygon`](crate::shape::Polygon), its
+/// fields are defined as functions of `Index`, or of the [`Context`] for setters ending in
+/// `_context_fn`.
+#[derive(bon::Builder)]
+pub struct Superformula<I> {
+    /// The rotational symmetry of the shape
+    #[builder(
+        setters(name = m_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub m_fn: ContextFn<I, f64>,
+
+    /// The overall exponent, where smaller values pinch the shape towards its center
+    #[builder(
+        setters(name = n1_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub n1_fn: ContextFn<I, f64>,
+
+    /// The exponent of the cosine term
+    #[builder(
+        setters(name = n2_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub n2_fn: ContextFn<I, f64>,
+
+    /// The exponent of the sine term
+    #[builder(
+        setters(name = n3_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub n3_fn: ContextFn<I, f64>,
+
+    /// The scale of the cosine term
+    #[builder(
+        default = (Box::new(|_| 1.0)),
+        setters(name = a_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub a_fn: ContextFn<I, f64>,
+
+    /// The scale of the sine term
+    #[builder(
+        default = (Box::new(|_| 1.0)),
+        setters(name = b_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub b_fn: ContextFn<I, f64>,
+
+    /// The size of the shape
+    ///
+    /// This is the diameter of the smallest circle centered on the point that encloses the shape.
+    #[builder(
+        setters(name = size_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub size_fn: ContextFn<I, f64>,
+
+    /// The rotation of the shape
+    ///
+    /// With no rotation, the shape is symmetric about the vertical axis.
+    #[builder(
+        default = (Box::new(|_| Angle::default())),
+        setters(name = rotation_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Angle + 'static| Box::new(func)
+    )]
+    pub rotation_fn: ContextFn<I, Angle>,
+
+    /// The color of the shape
+    #[builder(
+        setters(name = color_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Color + 'static| Box::new(func)
+    )]
+    pub color_fn: ContextFn<I, Color>,
+}
+
+impl<I> Superformula<I> {
+    fn radius(phi: f64, m: f64, n1: f64, n2: f64, n3: f64, a: f64, b: f64) -> f64 {
+        let cos_term = ((m * phi / 4.0).cos() / a).abs().powf(n2);
+        let sin_term = ((m * phi / 4.0).sin() / b).abs().powf(n3);
+
+        (cos_term + sin_term).powf(-1.0 / n1)
+    }
+}
+
+impl<I> Shape for Superformula<I> {
+    type Index = I;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let (m, n1, n2, n3) = (
+            (self.m_fn)(context),
+            (self.n1_fn)(context),
+            (self.n2_fn)(context),
+            (self.n3_fn)(context),
+        );
+        let (a, b) = ((self.a_fn)(context), (self.b_fn)(context));
+        let size = (self.size_fn)(context) / 2.0;
+        let rotation = Angle::Radian(-PI / 2.0) + (self.rotation_fn)(context);
+
+        // Radii can be infinite where a term reaches zero, so those are clamped to the largest
+        // finite radius at evenly spaced angles. Finite radii are kept, however sharp their peaks.
+        let radius = |phi: f64| Self::radius(phi, m, n1, n2, n3, a, b);
+        let clamp = (0..360)
+            .map(|i| radius(f64::from(i).to_radians()))
+            .filter(|r| r.is_finite())
+            .fold(0.0, f64::max);
+        if !(clamp > 0.0 && size > 0.0) {
+            return (Path::new(), BoundingBox::empty());
+        }
+
+        // The curve is sampled before it is scaled, so the tolerance is scaled to match. Sharp
+        // peaks only make the curve larger than `clamp`, which makes the tolerance stricter.
+        let points = curve::sample_closed_curve(
+            |phi| Coordinate::Polar {
+                r: Some(radius(phi)).filter(|r| r.is_finite()).unwrap_or(clamp),
+                phi: Angle::Radian(phi) + rotation,
+            },
+            0.0,
+            2.0 * PI,
+            TOLERANCE * clamp / size,
+        );
+
+        // The largest radius is taken from the points that are drawn, so the size is exact
+        let max_radius = points
+            .iter()
+            .map(|point| point.to_polar().0.abs())
+            .fold(0.0, f64::max);
+        let scale = size / max_radius;
+        let points: Vec<_> = points.into_iter().map(|point| point * scale).collect();
+
+        // The spline can bulge past the points it passes through
+        let data = curve::smooth_closed_data(&points);
+        let bb = BezierPath::from_data(&data).bounding_box();
+
+        let color = (self.color_fn)(context);
+        (
+            Path::new()
+                .set("stroke", "none")
+                .set("d", data)
+                .set("fill", color.to_svg_color())
+                .set("fill-opacity", color.to_opacity_percent()),
+            bb,
+        )
+    }
+}
+
+impl<I, S: State> SuperformulaBuilder<I, S> {
+    impl_index_setter!(SuperformulaBuilder, m_fn -> m_context_fn: f64, SetMFn, MFn);
+    impl_index_setter!(SuperformulaBuilder, n1_fn -> n1_context_fn: f64, SetN1Fn, N1Fn);
+    impl_index_setter!(SuperformulaBuilder, n2_fn -> n2_context_fn: f64, SetN2Fn, N2Fn);
+    impl_index_setter!(SuperformulaBuilder, n3_fn -> n3_context_fn: f64, SetN3Fn, N3Fn);
+    impl_index_setter!(SuperformulaBuilder, a_fn -> a_context_fn: f64, SetAFn, AFn);
+    impl_index_setter!(SuperformulaBuilder, b_fn -> b_context_fn: f64, SetBFn, BFn);
+    impl_index_setter!(SuperformulaBuilder, size_fn -> size_context_fn: f64, SetSizeFn, SizeFn);
+    impl_index_setter!(SuperformulaBuilder, rotation_fn -> rotation_context_fn: Angle, SetRotationFn, RotationFn);
+    impl_index_setter!(SuperformulaBuilder, color_fn -> color_context_fn: Color, SetColorFn, ColorFn);
+
+    impl_static_setter!(SuperformulaBuilder, m -> m_fn: f64, SetMFn, MFn);
+    impl_static_setter!(SuperformulaBuilder, n1 -> n1_fn: f64, SetN1Fn, N1Fn);
+    impl_static_setter!(SuperformulaBuilder, n2 -> n2_fn: f64, SetN2Fn, N2Fn);
+    impl_static_setter!(SuperformulaBuilder, n3 -> n3_fn: f64, SetN3Fn, N3Fn);
+    impl_static_setter!(SuperformulaBuilder, a -> a_fn: f64, SetAFn, AFn);
+    impl_static_setter!(SuperformulaBuilder, b -> b_fn: f64, SetBFn, BFn);
+    impl_static_setter!(SuperformulaBuilder, size -> size_fn: f64, SetSizeFn, SizeFn);
+    impl_static_setter!(SuperformulaBuilder, rotation -> rotation_fn: Angle, SetRotationFn, RotationFn);
+    impl_static_setter!(SuperformulaBuilder, color -> color_fn: Color, SetColorFn, ColorFn);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bb_contains_the_spline() {
+        let shape = Superformula::builder()
+            .m(5.0)
+            .n1(0.3)
+            .n2(0.3)
+            .n3(0.3)
+            .size(100.0)
+            .color(Color::new(0, 0, 0, 255))
+            .build();
+
+        let context = Context::new((), Coordinate::origin(), (100.0, 100.0), 1, 0);
+        let (path, bb) = shape.generate_path_and_bb(&context);
+        let (min_x, min_y) = bb.min().to_cartesian();
+        let (max_x, max_y) = bb.max().to_cartesian();
+
+        let points = BezierPath::from_path(&path).flatten(0.1, 1000);
+        assert!(points.iter().flatten().all(|point| {
+            let (x, y) = point.to_cartesian();
+            min_x - 1e-3 <= x && x <= max_x + 1e-3 && min_y - 1e-3 <= y && y <= max_y + 1e-3
+        }));
+    }
+
+    #[test]
+    fn sharp_peaks_between_degrees_are_kept() {
+        // The highest peaks are at odd multiples of 22.5°, half a degree from the nearest whole
+        // degrees, where the radius has already fallen to less than half of the peak
+        let shape = Superformula::builder()
+            .m(16.0)
+            .n1(0.05)
+            .n2(1.0)
+            .n3(1.0)
+            .b(1.2)
+            .size(100.0)
+            .color(Color::new(0, 0, 0, 255))
+            .build();
+
+        let context = Context::new((), Coordinate::origin(), (100.0, 100.0), 1, 0);
+        let (path, _) = shape.generate_path_and_bb(&context);
+        let points = BezierPath::from_path(&path).flatten(0.1, 1000);
+        let points: Vec<_> = points
+            .iter()
+            .flatten()
+            .map(|point| point.to_polar())
+            .collect();
+
+        let max = points.iter().map(|(r, _)| *r).fold(0.0, f64::max);
+        assert!((max - 50.0).abs() < 0.5, "{max}");
+
+        // Clamping to the largest radius at whole degrees would flatten each peak into an arc a
+        // degree wide
+        for (r, phi) in points.iter().filter(|(r, _)| *r > 45.0) {
+            let from_peak = (phi.to_degree().rem_euclid(45.0) - 22.5).abs();
+            assert!(from_peak < 0.1, "{r} at {phi:?}");
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! A shape made from the outlines of text
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use svg::node::element::Path;
+use ttf_parser::{Face, OutlineBuilder};
+
+use crate::{
+    Color, Error,
+    geometry::{Angle, BezierPath, BoundingBox, Coordinate, Segment},
+    shape::{Context, ContextFn, Shape},
+};
+use text_builder::{IsUnset, SetColorFn, SetRotationFn, SetSizeFn, SetTextFn, State};
+
+/// A TrueType or OpenType font
+///
+/// The outlines of glyphs are cached as they are drawn, and clones of a font share the cache.
+#[derive(Debug, Clone)]
+pub struct Font {
+    data: Rc<Vec<u8>>,
+    index: u32,
+    glyphs: Rc<RefCell<HashMap<char, Option<Glyph>>>>,
+}
+
+/// The outline of a glyph and how far it advances the line, scaled to a font size of `1.0`
+#[derive(Debug)]
+struct Glyph {
+    segments: Vec<Segment>,
+    advance: f64,
+}
+
+impl Font {
+    /// Load a font from a TTF or OTF file
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// Load a font from the contents of a TTF or OTF file
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, Error> {
+        Face::parse(&data, 0)?;
+
+        Ok(Self {
+            data: Rc::new(data),
+            index: 0,
+            glyphs: Rc::default(),
+        })
+    }
+
+    /// The outlines of `text`, set on a single line
+    ///
+    /// The outlines are scaled so that the font size is `1.0`, with the baseline at `y = 0.0`.
+    /// Characters that are missing from the font are skipped.
+    pub fn outline(&self, text: &str) -> BezierPath {
+        let mut glyphs = self.glyphs.borrow_mut();
+        let missing = text
+            .chars()
+            .filter(|c| !glyphs.contains_key(c))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            let Ok(face) = Face::parse(&self.data, self.index) else {
+                return BezierPath::default();
+            };
+            for c in missing {
+                glyphs.entry(c).or_insert_with(|| Self::glyph(&face, c));
+            }
+        }
+
+        let mut segments = Vec::new();
+        let mut offset = Coordinate::origin();
+        for glyph in text.chars().filter_map(|c| glyphs[&c].as_ref()) {
+            segments.extend(glyph.segments.iter().map(|segment| match *segment {
+                Segment::MoveTo(point) => Segment::MoveTo(point + offset),
+                Segment::LineTo(point) => Segment::LineTo(point + offset),
+                Segment::CubicTo(control1, control2, end) => {
+                    Segment::CubicTo(control1 + offset, control2 + offset, end + offset)
+                }
+                Segment::Close => Segment::Close,
+            }));
+            offset = offset
+                + Coordinate::Cartesian {
+                    x: glyph.advance,
+                    y: 0.0,
+                };
+        }
+
+        BezierPath::new(segments)
+    }
+
+    /// Parse the glyph of `c` from the face, if the font has one
+    fn glyph(face: &Face, c: char) -> Option<Glyph> {
+        let id = face.glyph_index(c)?;
+        let mut builder = GlyphBuilder {
+            segments: Vec::new(),
+            scale: 1.0 / f64::from(face.units_per_em()),
+            current: Coordinate::origin(),
+        };
+        face.outline_glyph(id, &mut builder);
+
+        Some(Glyph {
+            segments: builder.segments,
+            advance: f64::from(face.glyph_hor_advance(id).unwrap_or_default()) * builder.scale,
+        })
+    }
+}
+
+/// Collects the outline of each glyph, flipping it so that `y` points down as in SVG
+struct GlyphBuilder {
+    segments: Vec<Segment>,
+    scale: f64,
+    current: Coordinate,
+}
+
+impl GlyphBuilder {
+    fn point(&self, x: f32, y: f32) -> Coordinate {
+        Coordinate::Cartesian {
+            x: f64::from(x) * self.scale,
+            y: -f64::from(y) * self.scale,
+        }
+    }
+}
+
+impl OutlineBuilder for GlyphBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.current = self.point(x, y);
+        self.segments.push(Segment::MoveTo(self.current));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.current = self.point(x, y);
+        self.segments.push(Segment::LineTo(self.current));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let control = self.point(x1, y1);
+        let end = self.point(x, y);
+        self.segments.push(Segment::CubicTo(
+            self.current.lerp(&control, 2.0 / 3.0),
+            end.lerp(&control, 2.0 / 3.0),
+            end,
+        ));
+        self.current = end;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.current = self.point(x, y);
+        self.segments.push(Segment::CubicTo(
+            self.point(x1, y1),
+            self.point(x2, y2),
+            self.current,
+        ));
+    }
+
+    fn close(&mut self) {
+        self.segments.push(Segment::Close);
+    }
+}
+
+/// A shape made from the outlines of text, such as a kanji or a monogram
+///
+/// The text is set on a single line using a [`Font`], and centered on each point. Like
+/// [`Polygon`](crate::shape::Polygon), its fields are defined as functions of `Index`, or of the
+/// [`Context`] for setters ending in `_context_fn`.
+#[derive(bon::Builder)]
+pub struct Text<I> {
+    /// The font used to draw the text
+    pub font: Font,
+
+    /// The text that is drawn
+    #[builder(
+        setters(name = text_context_fn),
+        with = |func: impl Fn(&Context<I>) -> String + 'static| Box::new(func)
+    )]
+    pub text_fn: ContextFn<I, String>,
+
+    /// The font size of the text
+    #[builder(
+        setters(name = size_context_fn),
+        with = |func: impl Fn(&Context<I>) -> f64 + 'static| Box::new(func)
+    )]
+    pub size_fn: ContextFn<I, f64>,
+
+    /// The rotation of the text
+    #[builder(
+        default = (Box::new(|_| Angle::default())),
+        setters(name = rotation_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Angle + 'static| Box::new(func)
+    )]
+    pub rotation_fn: ContextFn<I, Angle>,
+
+    /// The color of the text
+    #[builder(
+        setters(name = color_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Color + 'static| Box::new(func)
+    )]
+    pub color_fn: ContextFn<I, Color>,
+}
+
+impl<I> Shape for Text<I> {
+    type Index = I;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let outline = self.font.outline(&(self.text_fn)(context));
+        let bb = outline.bounding_box();
+        let center = bb.min() + bb.span() / 2.0;
+        let size = (self.size_fn)(context);
+        let rotation = (self.rotation_fn)(context);
+
+        let path = outline
+            .map(|point| (point - center) * size)
+            .rotate(rotation);
+        let color = (self.color_fn)(context);
+
+        (
+            Path::new()
+                .set("stroke", "none")
+                .set("d", path.to_data())
+                .set("fill", color.to_svg_color())
+                .set("fill-opacity", color.to_opacity_percent()),
+            path.bounding_box(),
+        )
+    }
+}
+
+impl<I, S: State> TextBuilder<I, S> {
+    impl_index_setter!(TextBuilder, text_fn -> text_context_fn: String, SetTextFn, TextFn);
+    impl_index_setter!(TextBuilder, size_fn -> size_context_fn: f64, SetSizeFn, SizeFn);
+    impl_index_setter!(TextBuilder, rotation_fn -> rotation_context_fn: Angle, SetRotationFn, RotationFn);
+    impl_index_setter!(TextBuilder, color_fn -> color_context_fn: Color, SetColorFn, ColorFn);
+
+    impl_static_setter!(TextBuilder, size -> size_fn: f64, SetSizeFn, SizeFn);
+    impl_static_setter!(TextBuilder, rotation -> rotation_fn: Angle, SetRotationFn, RotationFn);
+    impl_static_setter!(TextBuilder, color -> color_fn: Color, SetColorFn, ColorFn);
+
+    pub fn text(self, value: impl Into<String>) -> TextBuilder<I, SetTextFn<S>>
+    where
+        S::TextFn: IsUnset,
+    {
+        let value = value.into();
+        self.text_fn(move |_| value.clone())
+    }
+}
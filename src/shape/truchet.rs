@@ -0,0 +1,363 @@
+//! Truchet tiles, which join up with their neighbors to form a pattern across a lattice
+use std::f64::consts::PI;
+
+use rand::{RngExt, SeedableRng, rngs::StdRng};
+use svg::node::element::{Path, path::Data};
+
+use crate::{
+    Color,
+    geometry::{Angle, BezierPath, BoundingBox, Coordinate},
+    point_set::lattice::Lattice,
+    shape::{Context, ContextFn, Shape, context::mix_seed},
+};
+use truchet_builder::{IsUnset, SetColorFn, SetOrientationFn, SetSize, SetTiling, State};
+
+/// Used to derive the seed for random orientations from the seed of the context
+const TRUCHET_SEED: u64 = 0x0074_7275_6368_6574;
+
+/// The shape of the cells that tiles fill
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tiling {
+    /// Square cells, for square lattices
+    #[default]
+    Square,
+
+    /// Square cells turned by 45° with a corner up, for diamond lattices
+    Diamond,
+
+    /// Hexagonal cells with a pointy side up, for hexagonal lattices
+    Hexagonal,
+}
+
+impl Tiling {
+    /// The number of sides of each cell
+    fn sides(self) -> usize {
+        match self {
+            Tiling::Square | Tiling::Diamond => 4,
+            Tiling::Hexagonal => 6,
+        }
+    }
+
+    /// The turn of a cell from its sides being level with the axes
+    fn rotation(self) -> Angle {
+        match self {
+            Tiling::Square | Tiling::Hexagonal => Angle::Radian(0.0),
+            Tiling::Diamond => Angle::Radian(PI / 4.0),
+        }
+    }
+
+    /// The corners of a cell, where `size` is the distance between neighboring cells
+    fn corners(self, size: f64) -> Vec<Coordinate> {
+        let (r, start) = match self {
+            Tiling::Square => (size / 2.0_f64.sqrt(), -3.0 * PI / 4.0),
+            Tiling::Diamond => (size / 2.0_f64.sqrt(), -PI / 2.0),
+            Tiling::Hexagonal => (size / 3.0_f64.sqrt(), -PI / 2.0),
+        };
+        let sides = self.sides();
+
+        (0..sides)
+            .map(|k| Coordinate::Polar {
+                r,
+                phi: Angle::Radian(start + 2.0 * PI * k as f64 / sides as f64),
+            })
+            .collect()
+    }
+}
+
+/// The motif drawn on each tile
+#[derive(Debug, Clone, Default)]
+pub enum Motif {
+    /// Arcs around every other corner, joining the middles of the sides
+    ///
+    /// On square tiles these are the quarter circles of Smith's tiles.
+    #[default]
+    Arcs,
+
+    /// A diagonal from one corner to the opposite corner, splitting the tile in half
+    ///
+    /// On square tiles this is the original Truchet tile.
+    Diagonal,
+
+    /// A custom motif, where the tile spans `-0.5..=0.5`
+    ///
+    /// The motif should meet the sides of the tile at the same points on every side to connect
+    /// seamlessly with its neighbors.
+    Custom(BezierPath),
+}
+
+/// Whether the motif is filled or drawn as a line
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Style {
+    /// Fill the area of the motif
+    ///
+    /// Arcs fill the sectors at the corners, and diagonals fill one half of the tile.
+    #[default]
+    Fill,
+
+    /// Draw the lines of the motif with a stroke of `width`, such as for a pen plotter
+    Stroke { width: f64 },
+}
+
+/// A Truchet tile, which is turned at each point so that neighboring tiles form a pattern
+///
+/// The `size` is the distance between neighboring points, so that tiles connect seamlessly. The
+/// builder's `try_lattice` takes the size and tiling from a square, diamond or hexagonal
+/// [`Lattice`]. By default, the orientation is chosen randomly, seeded from the [`Context`]. It
+/// can also be chosen by a function, or by a [`NoiseField`](crate::noise::NoiseField):
+///
+/// ```rust,ignore
+/// let truchet = Truchet::builder()
+///     .try_lattice(&lattice)
+///     .expect("a square, diamond or hexagonal lattice")
+///     .orientation_context_fn(field.map_fn(|t| (t * 2.0) as u8))
+///     .color(indigo)
+///     .build();
+/// ```
+#[derive(bon::Builder)]
+pub struct Truchet<I> {
+    /// The shape of the cells
+    #[builder(default)]
+    pub tiling: Tiling,
+
+    /// The distance between neighboring tiles
+    pub size: f64,
+
+    /// The motif drawn on the tile
+    #[builder(default)]
+    pub motif: Motif,
+
+    /// Whether the motif is filled or stroked
+    #[builder(default)]
+    pub style: Style,
+
+    /// The number of turns of the tile, each by one corner of the cell
+    #[builder(
+        default = Box::new(|context| random_orientation(context)),
+        setters(name = orientation_context_fn),
+        with = |func: impl Fn(&Context<I>) -> u8 + 'static| Box::new(func)
+    )]
+    pub orientation_fn: ContextFn<I, u8>,
+
+    /// The color of the motif
+    #[builder(
+        setters(name = color_context_fn),
+        with = |func: impl Fn(&Context<I>) -> Color + 'static| Box::new(func)
+    )]
+    pub color_fn: ContextFn<I, Color>,
+}
+
+/// The size and tiling of the cells around the points of `lattice`, if tiles can fill them
+fn lattice_tiling(lattice: &Lattice) -> Option<(f64, Tiling)> {
+    let is_near = |a: f64, b: f64| (a - b).abs() <= 1e-6 * a.abs().max(b.abs());
+    let angle = lattice.theta.to_degree();
+
+    if is_near(angle, 90.0) && is_near(lattice.len_a, lattice.len_b) {
+        Some((lattice.len_a, Tiling::Square))
+    } else if is_near(angle, 45.0) && is_near(lattice.len_a, lattice.len_b * 2.0_f64.sqrt()) {
+        // Each point neighbors the points along `b`, half way along the next row
+        Some((lattice.len_b, Tiling::Diamond))
+    } else if is_near(angle, 60.0) && is_near(lattice.len_a, lattice.len_b) {
+        Some((lattice.len_a, Tiling::Hexagonal))
+    } else {
+        None
+    }
+}
+
+fn random_orientation<I>(context: &Context<I>) -> u8 {
+    StdRng::seed_from_u64(mix_seed(context.seed, TRUCHET_SEED)).random()
+}
+
+impl<I> Truchet<I> {
+    /// Generate the path data for the motif turned by `orientation`
+    fn generate_data(&self, orientation: u8) -> Data {
+        let sides = self.tiling.sides();
+        let mut corners = self.tiling.corners(self.size);
+        corners.rotate_left(usize::from(orientation) % sides);
+
+        let middle = |k: usize| corners[k % sides].lerp(&corners[(k + 1) % sides], 0.5);
+        let filled = self.style == Style::Fill;
+
+        match &self.motif {
+            Motif::Arcs => (0..sides).step_by(2).fold(Data::new(), |data, k| {
+                let center = corners[k];
+                let start = middle(k + sides - 1);
+                let end = middle(k);
+                let radius = (center.dist(&start) * 1000.0).round() / 1000.0;
+
+                // The arc bends clockwise if it turns clockwise around its center
+                let (start_x, start_y) = (start - center).to_cartesian();
+                let (end_x, end_y) = (end - center).to_cartesian();
+                let sweep = if start_x * end_y - start_y * end_x > 0.0 {
+                    1
+                } else {
+                    0
+                };
+
+                let data = if filled {
+                    data.move_to(center.to_rounded_cartesian(3))
+                        .line_to(start.to_rounded_cartesian(3))
+                } else {
+                    data.move_to(start.to_rounded_cartesian(3))
+                };
+                let (end_x, end_y) = end.to_rounded_cartesian(3);
+                let data = data.elliptical_arc_to((radius, radius, 0, 0, sweep, end_x, end_y));
+
+                if filled { data.close() } else { data }
+            }),
+            Motif::Diagonal => {
+                if filled {
+                    corners[1..=sides / 2]
+                        .iter()
+                        .fold(
+                            Data::new().move_to(corners[0].to_rounded_cartesian(3)),
+                            |data, corner| data.line_to(corner.to_rounded_cartesian(3)),
+                        )
+                        .close()
+                } else {
+                    Data::new()
+                        .move_to(corners[0].to_rounded_cartesian(3))
+                        .line_to(corners[sides / 2].to_rounded_cartesian(3))
+                }
+            }
+            Motif::Custom(path) => {
+                let rotation = self.tiling.rotation()
+                    + Angle::Radian(2.0 * PI * f64::from(orientation) / sides as f64);
+                path.map(|point| (point * self.size).rotate(rotation))
+                    .to_data()
+            }
+        }
+    }
+}
+
+impl<I> Shape for Truchet<I> {
+    type Index = I;
+
+    fn generate_path_and_bb(&self, context: &Context<Self::Index>) -> (Path, BoundingBox) {
+        let data = self.generate_data((self.orientation_fn)(context));
+        let color = (self.color_fn)(context);
+        let bb = BoundingBox::from_points(&self.tiling.corners(self.size));
+
+        let path = Path::new().set("d", data);
+        match self.style {
+            Style::Fill => (
+                path.set("stroke", "none")
+                    .set("fill", color.to_svg_color())
+                    .set("fill-opacity", color.to_opacity_percent()),
+                bb,
+            ),
+            Style::Stroke { width } => {
+                let margin = Coordinate::Cartesian {
+                    x: width / 2.0,
+                    y: width / 2.0,
+                };
+                (
+                    path.set("fill", "none")
+                        .set("stroke", color.to_svg_color())
+                        .set("stroke-opacity", color.to_opacity_percent())
+                        .set("stroke-width", width),
+                    BoundingBox::new(bb.min() - margin, bb.max() + margin),
+                )
+            }
+        }
+    }
+}
+
+impl<I, S: State> TruchetBuilder<I, S> {
+    impl_index_setter!(TruchetBuilder, orientation_fn -> orientation_context_fn: u8, SetOrientationFn, OrientationFn);
+    impl_index_setter!(TruchetBuilder, color_fn -> color_context_fn: Color, SetColorFn, ColorFn);
+
+    impl_static_setter!(TruchetBuilder, orientation -> orientation_fn: u8, SetOrientationFn, OrientationFn);
+    impl_static_setter!(TruchetBuilder, color -> color_fn: Color, SetColorFn, ColorFn);
+
+    /// Take the size and tiling from the spacing of a [`Lattice`]
+    ///
+    /// Square lattices use square tiles, diamond lattices use square tiles turned by 45°, and
+    /// hexagonal lattices use hexagonal tiles, each the size of the cell around a point. Returns
+    /// `None` for any other lattice, such as a rectangular or rhombic lattice, whose cells can't
+    /// be filled with these tiles.
+    pub fn try_lattice(self, lattice: &Lattice) -> Option<TruchetBuilder<I, SetTiling<SetSize<S>>>>
+    where
+        S::Size: IsUnset,
+        S::Tiling: IsUnset,
+    {
+        let (size, tiling) = lattice_tiling(lattice)?;
+
+        Some(self.size(size).tiling(tiling))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point_set::{PointSet, lattice::Index};
+
+    /// Check that the tile at a point of `lattice` shares a side with the tiles at each of its
+    /// `neighbors`
+    fn assert_tiles_meet(lattice: &Lattice, neighbors: &[(u16, u16)]) {
+        let (size, tiling) = lattice_tiling(lattice).unwrap();
+        let at = |(u, v)| lattice.index_to_coordinate(&Index { u, v });
+
+        for neighbor in neighbors {
+            let offset = at(*neighbor) - at((2, 2));
+
+            let shared = tiling
+                .corners(size)
+                .iter()
+                .filter(|corner| {
+                    tiling
+                        .corners(size)
+                        .iter()
+                        .any(|other| corner.dist(&(*other + offset)) < 1e-9)
+                })
+                .count();
+            assert_eq!(shared, 2, "{tiling:?} tiles don't meet along {offset:?}");
+        }
+    }
+
+    #[test]
+    fn tiles_meet_on_supported_lattices() {
+        assert_tiles_meet(
+            &Lattice::square_builder()
+                .grid_size(5, 5)
+                .len_a(10.0)
+                .build(),
+            &[(1, 2), (3, 2), (2, 1), (2, 3)],
+        );
+        assert_tiles_meet(
+            &Lattice::diamond_builder()
+                .grid_size(5, 5)
+                .len_a(10.0)
+                .build(),
+            &[(1, 1), (2, 1), (1, 3), (2, 3)],
+        );
+        assert_tiles_meet(
+            &Lattice::hexagonal_builder()
+                .grid_size(5, 5)
+                .len_a(10.0)
+                .build(),
+            &[(1, 2), (3, 2), (1, 1), (2, 1), (1, 3), (2, 3)],
+        );
+    }
+
+    #[test]
+    fn other_lattices_have_no_tiling() {
+        let rectangular = Lattice::rectangular_builder()
+            .grid_size(5, 5)
+            .len_a(10.0)
+            .len_b(20.0)
+            .build();
+        let rhombic = Lattice::rhombic_builder()
+            .grid_size(5, 5)
+            .len_a(10.0)
+            .len_b(8.0)
+            .build();
+
+        assert!(lattice_tiling(&rectangular).is_none());
+        assert!(lattice_tiling(&rhombic).is_none());
+        assert!(
+            Truchet::<Index>::builder()
+                .try_lattice(&rectangular)
+                .is_none()
+        );
+    }
+}
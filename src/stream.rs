@@ -0,0 +1,159 @@
+//! Writing an SVG document one element at a time, without building it in memory
+use std::io;
+
+use svg::{Node, node::element::Element};
+
+/// The name of the element marking where the children of an open element go
+const MARKER: &str = "kanoko-stream-marker";
+
+/// The placeholder for the children of an element passed to [`Stream::open`]
+pub(crate) fn marker() -> Element {
+    Element::new(MARKER)
+}
+
+/// An element that has been opened, whose children are being written
+struct Open {
+    /// The start of the element, up to its first child
+    head: String,
+
+    /// The end of the element, after its last child
+    tail: String,
+
+    /// The whole element when it has no children
+    empty: String,
+
+    /// Whether the head has been written
+    started: bool,
+}
+
+/// Writes an SVG document to `writer` in the same format as `svg::Document`'s `Display`
+///
+/// Elements are opened and closed around their children, which are written as they come. The
+/// start of an element is only written along with its first child, as elements without children
+/// are written as a single tag.
+pub(crate) struct Stream<W: io::Write> {
+    writer: W,
+    open: Vec<Open>,
+}
+
+impl<W: io::Write> Stream<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Self {
+            writer,
+            open: Vec::new(),
+        }
+    }
+
+    /// Open an element, given once with a [`marker`] as its only child in the innermost group,
+    /// and once without any children there
+    pub(crate) fn open(&mut self, with_marker: impl Node, empty: impl Node) {
+        // Each child is written on a new line, as with `Display`
+        let with_marker = with_marker.to_string();
+        let marker = format!("<{MARKER}/>");
+        let (head, tail) = with_marker
+            .split_once(&marker)
+            .expect("the element contains the marker");
+
+        self.open.push(Open {
+            head: head.strip_suffix('\n').unwrap_or(head).to_string(),
+            tail: tail.to_string(),
+            empty: empty.to_string(),
+            started: false,
+        });
+    }
+
+    /// Write `element` as the next child of the innermost open element
+    pub(crate) fn write(&mut self, element: &Element) -> io::Result<()> {
+        self.start()?;
+        write!(self.writer, "\n{element}")
+    }
+
+    /// Close the innermost open element
+    pub(crate) fn close(&mut self) -> io::Result<()> {
+        let Some(open) = self.open.pop() else {
+            return Ok(());
+        };
+
+        if open.started {
+            write!(self.writer, "{}", open.tail)
+        } else {
+            self.start()?;
+            if !self.open.is_empty() {
+                writeln!(self.writer)?;
+            }
+            write!(self.writer, "{}", open.empty)
+        }
+    }
+
+    /// Write the heads of the open elements that haven't been started, now that they have a child
+    fn start(&mut self) -> io::Result<()> {
+        for (depth, open) in self.open.iter_mut().enumerate() {
+            if !open.started {
+                if 0 < depth {
+                    writeln!(self.writer)?;
+                }
+                write!(self.writer, "{}", open.head)?;
+                open.started = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Close every open element and flush the writer
+    pub(crate) fn finish(mut self) -> io::Result<()> {
+        while !self.open.is_empty() {
+            self.close()?;
+        }
+
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use svg::{
+        Document,
+        node::element::{Circle, Group},
+    };
+
+    use super::*;
+
+    #[test]
+    fn output_matches_document_display() {
+        let circle = || Element::from(Circle::new().set("r", 1));
+        let document = Document::new().set("width", 10);
+        let layer = Group::new().set("id", "layer");
+        let empty = Group::new().set("id", "empty");
+
+        let mut output = Vec::new();
+        let mut stream = Stream::new(&mut output);
+        stream.open(document.clone().add(marker()), document.clone());
+        stream.write(&circle()).unwrap();
+        stream.open(layer.clone().add(marker()), layer.clone());
+        stream.write(&circle()).unwrap();
+        stream.write(&circle()).unwrap();
+        stream.close().unwrap();
+        stream.open(empty.clone().add(marker()), empty.clone());
+        stream.close().unwrap();
+        stream.finish().unwrap();
+
+        let expected = document
+            .add(circle())
+            .add(layer.add(circle()).add(circle()))
+            .add(empty);
+        assert_eq!(String::from_utf8(output).unwrap(), expected.to_string());
+    }
+
+    #[test]
+    fn empty_document_is_a_single_tag() {
+        let document = Document::new().set("width", 10);
+
+        let mut output = Vec::new();
+        let mut stream = Stream::new(&mut output);
+        stream.open(document.clone().add(marker()), document.clone());
+        stream.finish().unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), document.to_string());
+    }
+}
@@ -0,0 +1,161 @@
+//! Symmetry of the [`Canvas`](crate::Canvas) about its center, for mandalas and medallions
+use std::f64::consts::PI;
+
+use crate::{
+    geometry::{Angle, Coordinate},
+    layout::Transform,
+};
+
+/// A symmetry of the whole canvas about its center
+///
+/// Only the points in one part of the canvas are used, and the shapes drawn at them are copied to
+/// the other parts. Each copy is the same shape, including any random choices, rotated or
+/// mirrored into place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    /// The wedge clockwise from the right of the center is rotated `n` times around the center
+    Rotational(u16),
+
+    /// The half of the wedge of [`Symmetry::Rotational`] that is clockwise from the right of the
+    /// center is mirrored into the other half, then rotated `n` times, as in a kaleidoscope
+    Kaleidoscope(u16),
+
+    /// The left half is mirrored onto the right half
+    MirrorX,
+
+    /// The top half is mirrored onto the bottom half
+    MirrorY,
+
+    /// The top left quarter is mirrored onto the other quarters
+    MirrorXY,
+}
+
+impl Symmetry {
+    /// Whether a point at `offset` from the center is in the part of the canvas that is copied
+    pub(crate) fn contains(self, offset: Coordinate) -> bool {
+        let (x, y) = offset.to_cartesian();
+        let angle = y.atan2(x).rem_euclid(2.0 * PI);
+
+        match self {
+            Symmetry::Rotational(n) => angle < 2.0 * PI / f64::from(n.max(1)),
+            Symmetry::Kaleidoscope(n) => angle <= PI / f64::from(n.max(1)),
+            Symmetry::MirrorX => x <= 0.0,
+            Symmetry::MirrorY => y <= 0.0,
+            Symmetry::MirrorXY => x <= 0.0 && y <= 0.0,
+        }
+    }
+
+    /// The transforms around the center that copy the part onto the whole canvas, starting with
+    /// the identity
+    pub(crate) fn transforms(self) -> Vec<Transform> {
+        let rotate = |turns: u16, n: u16| {
+            Transform::new().rotate(Angle::Radian(2.0 * PI * f64::from(turns) / f64::from(n)))
+        };
+
+        match self {
+            Symmetry::Rotational(n) => {
+                let n = n.max(1);
+                (0..n).map(|i| rotate(i, n)).collect()
+            }
+            Symmetry::Kaleidoscope(n) => {
+                let n = n.max(1);
+                (0..n)
+                    .flat_map(|i| {
+                        // Mirroring across the middle of the wedge is the same as flipping it
+                        // over the right of the center and turning it by a whole wedge
+                        let mirror = Transform::new()
+                            .mirror_y()
+                            .rotate(Angle::Radian(2.0 * PI * f64::from(i + 1) / f64::from(n)));
+                        [rotate(i, n), mirror]
+                    })
+                    .collect()
+            }
+            Symmetry::MirrorX => vec![Transform::new(), Transform::new().mirror_x()],
+            Symmetry::MirrorY => vec![Transform::new(), Transform::new().mirror_y()],
+            Symmetry::MirrorXY => vec![
+                Transform::new(),
+                Transform::new().mirror_x(),
+                Transform::new().mirror_y(),
+                Transform::new().mirror_x().mirror_y(),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYMMETRIES: [Symmetry; 7] = [
+        Symmetry::Rotational(1),
+        Symmetry::Rotational(5),
+        Symmetry::Kaleidoscope(1),
+        Symmetry::Kaleidoscope(6),
+        Symmetry::MirrorX,
+        Symmetry::MirrorY,
+        Symmetry::MirrorXY,
+    ];
+
+    /// Points around the center, at `steps` angles that avoid the edges of the parts
+    fn around(steps: u32) -> impl Iterator<Item = Coordinate> {
+        (0..steps).map(move |i| {
+            let angle = 2.0 * PI * (f64::from(i) + 0.37) / f64::from(steps);
+            Coordinate::Cartesian {
+                x: 10.0 * angle.cos(),
+                y: 10.0 * angle.sin(),
+            }
+        })
+    }
+
+    #[test]
+    fn transforms_start_with_the_identity() {
+        for symmetry in SYMMETRIES {
+            assert!(symmetry.transforms()[0].is_identity(), "{symmetry:?}");
+        }
+    }
+
+    #[test]
+    fn each_copy_is_a_transform() {
+        let counts = SYMMETRIES.map(|symmetry| symmetry.transforms().len());
+        assert_eq!(counts, [1, 5, 2, 12, 2, 2, 4]);
+        assert_eq!(Symmetry::Rotational(0).transforms().len(), 1);
+    }
+
+    #[test]
+    fn copies_do_not_overlap_the_part() {
+        for symmetry in SYMMETRIES {
+            for offset in around(360).filter(|&offset| symmetry.contains(offset)) {
+                for transform in &symmetry.transforms()[1..] {
+                    assert!(
+                        !symmetry.contains(transform.apply(offset)),
+                        "{symmetry:?} copies {offset:?} back into the part"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn copies_cover_the_canvas() {
+        for symmetry in SYMMETRIES {
+            let copies = around(3600)
+                .filter(|&offset| symmetry.contains(offset))
+                .flat_map(|offset| {
+                    symmetry
+                        .transforms()
+                        .into_iter()
+                        .map(move |t| t.apply(offset))
+                })
+                .collect::<Vec<_>>();
+
+            for target in around(360) {
+                let nearest = copies
+                    .iter()
+                    .map(|&copy| (copy - target).to_cartesian())
+                    .map(|(x, y)| x.hypot(y))
+                    .fold(f64::INFINITY, f64::min);
+                assert!(nearest < 0.05, "{symmetry:?} leaves {target:?} uncovered");
+            }
+        }
+    }
+}
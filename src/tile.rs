@@ -0,0 +1,85 @@
+//! Seamless tiles of the [`Canvas`](crate::Canvas), for patterns that are printed as a repeat
+use std::ops::RangeInclusive;
+
+use itertools::iproduct;
+
+use crate::geometry::Coordinate;
+
+/// How copies of a tile are placed next to each other to make a repeat
+///
+/// Shapes crossing an edge of a tiled canvas are drawn again on the opposite edge, where they
+/// continue in the neighboring tile.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Repeat {
+    /// A simple grid of tiles, aka a block repeat
+    #[default]
+    Straight,
+
+    /// Each column of tiles is shifted down by half the height of a tile
+    HalfDrop,
+
+    /// Each row of tiles is shifted right by half the width of a tile
+    Brick,
+}
+
+impl Repeat {
+    /// The offsets from a tile of `size` to the next tile along each axis
+    ///
+    /// Every tile of the repeat is at a whole number of each offset.
+    fn steps(self, (width, height): (f64, f64)) -> (Coordinate, Coordinate) {
+        let (shift_y, shift_x) = match self {
+            Repeat::Straight => (0.0, 0.0),
+            Repeat::HalfDrop => (height / 2.0, 0.0),
+            Repeat::Brick => (0.0, width / 2.0),
+        };
+
+        (
+            Coordinate::Cartesian {
+                x: width,
+                y: shift_y,
+            },
+            Coordinate::Cartesian {
+                x: shift_x,
+                y: height,
+            },
+        )
+    }
+
+    /// The offsets of a tile of `size` and the tiles around it, starting with the tile itself
+    pub(crate) fn neighbors(self, size: (f64, f64)) -> impl Iterator<Item = Coordinate> {
+        self.tiles(size, -1..=1, -1..=1)
+    }
+
+    /// The offsets of the tiles at `columns` and `rows` steps from a tile of `size`
+    pub(crate) fn tiles(
+        self,
+        size: (f64, f64),
+        columns: RangeInclusive<i32>,
+        rows: RangeInclusive<i32>,
+    ) -> impl Iterator<Item = Coordinate> {
+        let (column_step, row_step) = self.steps(size);
+        let mut tiles: Vec<(i32, i32)> = iproduct!(columns, rows).collect();
+        tiles.sort_by_key(|(column, row)| (column.abs() + row.abs(), *column, *row));
+
+        tiles
+            .into_iter()
+            .map(move |(column, row)| column_step * f64::from(column) + row_step * f64::from(row))
+    }
+
+    /// Move `coordinate` onto the tile of `size` at the origin, from wherever it is in the repeat
+    pub(crate) fn wrap(self, coordinate: Coordinate, (width, height): (f64, f64)) -> Coordinate {
+        let (mut x, mut y) = coordinate.to_cartesian();
+
+        // Moving across a column or row of tiles also shifts along the other axis
+        match self {
+            Repeat::Straight => {}
+            Repeat::HalfDrop => y -= (x / width).floor() * height / 2.0,
+            Repeat::Brick => x -= (y / height).floor() * width / 2.0,
+        }
+
+        Coordinate::Cartesian {
+            x: x.rem_euclid(width),
+            y: y.rem_euclid(height),
+        }
+    }
+}